@@ -0,0 +1,182 @@
+//! Compares the cost of the simulation's 360 unit FixedPoint against a power of two resolution
+//! on the Pygamer's Cortex-M4. Each benchmark is timed with the cycle counter, and the average
+//! cycles per iteration are drawn on the display
+//!
+//! Run with `cargo run --release --example fixed_point_benchmark`
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use core::hint::black_box;
+
+#[cfg(not(feature = "panic_led"))]
+use panic_halt as _;
+use pygamer::{entry, hal, pac, Pins};
+
+use cortex_m::peripheral::DWT;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    text::Text,
+};
+use hal::clock::GenericClockController;
+use pac::{CorePeripherals, Peripherals};
+
+use simulation::FixedPoint;
+
+/// The number of times each benchmark is run
+const ITERATIONS: u32 = 10_000;
+/// The height of a line of text on the display
+const LINE_HEIGHT: i32 = 12;
+
+/// A line of text built without allocating
+struct Line {
+    buffer: [u8; 32],
+    length: usize,
+}
+
+impl Line {
+    fn new() -> Line {
+        Line {
+            buffer: [0; 32],
+            length: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buffer[..self.length]).unwrap_or("")
+    }
+}
+
+impl Write for Line {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.length + s.len();
+        if end > self.buffer.len() {
+            return Err(core::fmt::Error);
+        }
+        self.buffer[self.length..end].copy_from_slice(s.as_bytes());
+        self.length = end;
+        Ok(())
+    }
+}
+
+/// Runs a benchmark
+///
+/// Arguments:
+/// * `benchmark`: Called once per iteration with the iteration number
+///
+/// Return value:
+/// The average number of cycles taken per iteration
+fn measure<F: FnMut(u32)>(mut benchmark: F) -> u32 {
+    let start = DWT::get_cycle_count();
+    for iteration in 0..ITERATIONS {
+        benchmark(black_box(iteration));
+    }
+    DWT::get_cycle_count().wrapping_sub(start) / ITERATIONS
+}
+
+/// Maps a position on the board to pixels, using 12 pixel squares as the renderer does. A power
+/// of two resolution maps over 16 squares, so that the shift is used rather than the divide
+///
+/// Arguments:
+/// * `position`: The position to map
+fn map_position<const UNITS: i16>(position: FixedPoint<UNITS>) -> i16 {
+    let squares = if UNITS.count_ones() == 1 { 16 } else { 12 };
+    position.map_to_i16(FixedPoint::new(0, 0), FixedPoint::new(squares, 0), 0, squares as i16 * 12)
+}
+
+/// Advances a position as a walker does each step, stopping on the centre of the next square
+///
+/// Arguments:
+/// * `position`: The position to advance
+/// * `speed`: How far to move
+///
+/// Return value:
+/// True if the centre of a square was reached
+fn walk_step<const UNITS: i16>(position: &mut FixedPoint<UNITS>, speed: FixedPoint<UNITS>) -> bool {
+    let units = UNITS as i32;
+    let past_centre = (position.scaled() - units / 2).rem_euclid(units);
+    *position += speed.min(FixedPoint::from_scaled(units - past_centre));
+    (position.scaled() - units / 2).rem_euclid(units) == 0
+}
+
+/// Draws a benchmark result on its own line
+///
+/// Arguments:
+/// * `display`: The display to draw on
+/// * `row`: The line to draw on
+/// * `name`: The name of the benchmark
+/// * `cycles`: The cycles per iteration
+fn draw_result<D: DrawTarget<Color = Rgb565>>(display: &mut D, row: i32, name: &str, cycles: u32) {
+    let mut line = Line::new();
+    let _ = write!(line, "{}: {}", name, cycles);
+    let style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
+    let _ = Text::new(line.as_str(), Point::new(4, 12 + row * LINE_HEIGHT), style).draw(display);
+}
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_internal_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut pins = Pins::new(peripherals.PORT).split();
+    let mut delay = hal::delay::Delay::new(core.SYST, &mut clocks);
+
+    let (mut display, _backlight) = pins
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            peripherals.TC2,
+            &mut delay,
+            &mut pins.port,
+        )
+        .unwrap();
+
+    core.DCB.enable_trace();
+    core.DWT.enable_cycle_counter();
+
+    // Positions sweep across the board so that neither path can be folded into a constant
+    let map_360 = measure(|iteration| {
+        let position = FixedPoint::<360>::from_scaled((iteration % (12 * 360)) as i32);
+        black_box(map_position(black_box(position)));
+    });
+    let map_256 = measure(|iteration| {
+        let position = FixedPoint::<256>::from_scaled((iteration % (12 * 256)) as i32);
+        black_box(map_position(black_box(position)));
+    });
+
+    // Mice cross a square in 60 steps. 256 does not divide by 60, so the nearest speed is used
+    let mut position_360 = FixedPoint::<360>::new(0, 0);
+    let walk_360 = measure(|_| {
+        black_box(walk_step(&mut position_360, black_box(FixedPoint::new(0, 6))));
+        if position_360.integer_part() >= 12 {
+            position_360 = FixedPoint::new(0, 0);
+        }
+    });
+    let mut position_256 = FixedPoint::<256>::new(0, 0);
+    let walk_256 = measure(|_| {
+        black_box(walk_step(&mut position_256, black_box(FixedPoint::new(0, 4))));
+        if position_256.integer_part() >= 12 {
+            position_256 = FixedPoint::new(0, 0);
+        }
+    });
+
+    let _ = display.clear(Rgb565::BLACK);
+    draw_result(&mut display, 0, "map_to_i16 360", map_360);
+    draw_result(&mut display, 1, "map_to_i16 256", map_256);
+    draw_result(&mut display, 2, "walk step 360", walk_360);
+    draw_result(&mut display, 3, "walk step 256", walk_256);
+
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
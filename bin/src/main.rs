@@ -27,7 +27,7 @@ use pac::{CorePeripherals, Peripherals};
 use st7735_lcd::ST7735;
 
 use common::input::InputState;
-use maps::E1M1;
+use maps::MAPS;
 use platform::input::{read_input, JoystickReaderWithAdc};
 use simulation::{StateMachine, World};
 
@@ -121,7 +121,7 @@ fn main() -> ! {
     tcounter.start(60.hz());
 
     let mut game_state = GameState {
-        state_machine: StateMachine::new(World::load(E1M1)),
+        state_machine: StateMachine::new(World::load(*MAPS[0])),
     };
 
     let mut outputs = Outputs {
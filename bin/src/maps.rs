@@ -1,5 +1,8 @@
 use world_macros::puzzle;
 
+/// Every map shipped, in the order they are played
+pub static MAPS: [&[u8; 199]; 1] = [&E1M1];
+
 // The first level of OG ChuChu rocket
 pub static E1M1: [u8; 199] = puzzle!("Where to go?", "Sega",
 "┌───────────────────────────────────────────────────────────┐"
@@ -14,9 +14,9 @@ impl ArrowStock {
     /// Creates an empty ArrowStock
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{ArrowStock, Direction};
+    /// use simulation::{ArrowStock, Direction};
     /// let mut arrow_stock = ArrowStock::new();
-    /// arrow_stock[Direction::Left] = 10
+    /// arrow_stock[Direction::Left] = 10;
     /// assert_eq!(0,  arrow_stock[Direction::Up]);
     /// assert_eq!(0,  arrow_stock[Direction::Down]);
     /// assert_eq!(10, arrow_stock[Direction::Left]);
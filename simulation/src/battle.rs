@@ -0,0 +1,683 @@
+use crate::world::{DATA_SIZE, MAX_TILES, WORLD_HEIGHT, WORLD_WIDTH};
+use crate::{
+    BattleError, BattleEvent, Direction, EventSink, FixedPoint, PlacementError, Player,
+    RocketEventKind, Ruleset, TileType, WalkerType, World, WorldEvent, MAX_PLAYERS,
+};
+
+/// The points scored for each mouse rescued into a rocket
+const MOUSE_POINTS: u32 = 1;
+/// A cat entering a rocket takes this fraction of the owner's score, rounded down
+const CAT_PENALTY_DIVISOR: u32 = 3;
+/// The number of ticks before an arrow placed in a battle disappears
+pub const BATTLE_ARROW_LIFETIME: u32 = 600;
+/// The most arrows each player may have on the board at once
+pub const BATTLE_MAX_ARROWS: usize = 3;
+/// The number of ticks between chances of a battle event starting
+const EVENT_INTERVAL: u32 = 600;
+/// An event starts at each chance with a probability of one in this
+const EVENT_CHANCE: u32 = 2;
+/// The number of ticks a battle event lasts
+const EVENT_DURATION: u32 = 300;
+/// The number of ticks between spawners creating mice during Mouse Mania
+const MOUSE_MANIA_INTERVAL: u32 = 10;
+/// The number of ticks between spawners creating cats during Cat Mania
+const CAT_MANIA_INTERVAL: u32 = 90;
+
+/// How a battle ended
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BattleOutcome {
+    /// A single player finished with the highest score
+    Winner(Player),
+    /// Several players shared the highest score
+    Draw,
+}
+
+/// The scores after a battle tick
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BattleReport {
+    /// The score of each player, indexed by Player::index
+    pub scores: [u32; MAX_PLAYERS],
+    /// The number of ticks left in the round
+    pub ticks_remaining: u32,
+    /// How the battle ended, once the round timer has run out
+    pub outcome: Option<BattleOutcome>,
+    /// The event in progress, if any
+    pub active_event: Option<BattleEvent>,
+    /// The event that started during the tick, so that it can be announced
+    pub started_event: Option<BattleEvent>,
+}
+
+/// A timed battle between up to four players, played on a world. Each player owns rockets, and
+/// scores points for the mice rescued into them. A cat entering a rocket takes a share of its
+/// owner's score. Random events briefly change the rules for everybody. When the round timer runs
+/// out the highest score wins
+#[derive(Clone)]
+pub struct Battle<
+    const WIDTH: usize = WORLD_WIDTH,
+    const HEIGHT: usize = WORLD_HEIGHT,
+    const TILES: usize = MAX_TILES,
+    const DATA: usize = DATA_SIZE,
+> {
+    world: World<WIDTH, HEIGHT, TILES, DATA>,
+    /// The owner of the rocket on each tile, if any
+    rocket_owners: [Option<Player>; TILES],
+    scores: [u32; MAX_PLAYERS],
+    ticks_remaining: u32,
+    /// The ticks until the next chance of an event starting
+    ticks_until_event: u32,
+    /// The event in progress, and the ticks until it ends
+    active_event: Option<(BattleEvent, u32)>,
+    /// The mouse and cat speeds of the ruleset, restored when an event ends
+    normal_speeds: [FixedPoint; 2],
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const TILES: usize, const DATA: usize>
+    Battle<WIDTH, HEIGHT, TILES, DATA>
+{
+    /// Creates a battle on a world, played with the battle rules. Rockets entered by cats are hit
+    /// rather than destroyed, and start without an owner. Events are chosen using the world's
+    /// random number generator, so seed the world first for a repeatable battle
+    ///
+    /// Arguments:
+    /// * `world`: The world to play on
+    /// * `round_ticks`: The length of the round
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Battle, World};
+    /// let battle = Battle::new(World::new(), 3600);
+    /// assert_eq!(3600, battle.get_ticks_remaining());
+    /// ```
+    pub fn new(world: World<WIDTH, HEIGHT, TILES, DATA>, round_ticks: u32) -> Self {
+        Self::with_ruleset(world, round_ticks, Ruleset::battle())
+    }
+
+    /// Creates a battle on a world, played with custom rules. Speed events scale the speeds of
+    /// the ruleset, and restore them when they end
+    ///
+    /// Arguments:
+    /// * `world`: The world to play on
+    /// * `round_ticks`: The length of the round
+    /// * `ruleset`: The rules to play with
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Battle, FixedPoint, Ruleset, WalkerType, World};
+    /// let ruleset = Ruleset {
+    ///     mouse_speed: <FixedPoint>::new(0, 12),
+    ///     ..Ruleset::battle()
+    /// };
+    /// let battle = Battle::with_ruleset(World::new(), 3600, ruleset);
+    /// assert_eq!(
+    ///     <FixedPoint>::new(0, 12),
+    ///     battle.get_world().get_walker_speed(WalkerType::Mouse)
+    /// );
+    /// ```
+    pub fn with_ruleset(
+        mut world: World<WIDTH, HEIGHT, TILES, DATA>,
+        round_ticks: u32,
+        ruleset: Ruleset,
+    ) -> Self {
+        world.set_ruleset(ruleset);
+
+        Battle {
+            world,
+            rocket_owners: [None; TILES],
+            scores: [0; MAX_PLAYERS],
+            ticks_remaining: round_ticks,
+            ticks_until_event: EVENT_INTERVAL,
+            active_event: None,
+            normal_speeds: [ruleset.mouse_speed, ruleset.cat_speed],
+        }
+    }
+
+    /// Gives a rocket to a player
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate of the rocket
+    /// * `y`: The y coordinate of the rocket
+    /// * `player`: The new owner of the rocket
+    ///
+    /// Return value:
+    /// An error if there is no rocket on the tile
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Battle, BattleError, Player, World};
+    /// let mut battle = Battle::new(World::new(), 3600);
+    /// assert_eq!(Err(BattleError::NotARocket), battle.set_rocket_owner(0, 0, Player::One));
+    /// ```
+    pub fn set_rocket_owner(
+        &mut self,
+        x: usize,
+        y: usize,
+        player: Player,
+    ) -> Result<(), BattleError> {
+        if !matches!(self.world.get_arrow(x, y), TileType::Rocket | TileType::RocketDestroyed) {
+            return Err(BattleError::NotARocket);
+        }
+
+        self.rocket_owners[y * WIDTH + x] = Some(player);
+        Ok(())
+    }
+
+    /// Places an arrow for a player. The arrow disappears after BATTLE_ARROW_LIFETIME ticks, and
+    /// if the player already has BATTLE_MAX_ARROWS arrows on the board their oldest is removed.
+    /// Arrows can be placed while the battle runs, and can replace the player's own arrows
+    ///
+    /// Arguments:
+    /// * `player`: The player placing the arrow
+    /// * `x`: The x coordinate to place at
+    /// * `y`: The y coordinate to place at
+    /// * `direction`: The direction of the arrow
+    ///
+    /// Return value:
+    /// An error if the tile is a rocket or hole, or holds an arrow the player does not own
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Battle, Direction, Player, World};
+    /// let mut battle = Battle::new(World::new(), 3600);
+    /// assert_eq!(Ok(()), battle.place_arrow(Player::One, 0, 0, Direction::Up));
+    /// assert_eq!(Some(Player::One), battle.get_world().get_arrow_owner(0, 0));
+    /// ```
+    pub fn place_arrow(
+        &mut self,
+        player: Player,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> Result<(), PlacementError> {
+        self.world.place_owned_arrow(
+            x,
+            y,
+            direction,
+            player,
+            BATTLE_ARROW_LIFETIME,
+            BATTLE_MAX_ARROWS,
+        )
+    }
+
+    /// Gets the owner of the rocket at the specified location
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check
+    /// * `y`: The y coordinate to check
+    pub fn get_rocket_owner(&self, x: usize, y: usize) -> Option<Player> {
+        self.rocket_owners[y * WIDTH + x]
+    }
+
+    /// Gets the world the battle is played on
+    pub fn get_world(&self) -> &World<WIDTH, HEIGHT, TILES, DATA> {
+        &self.world
+    }
+
+    /// Gets the world the battle is played on, so that arrows can be set
+    pub fn get_world_mut(&mut self) -> &mut World<WIDTH, HEIGHT, TILES, DATA> {
+        &mut self.world
+    }
+
+    /// Gets the score of a player
+    pub fn get_score(&self, player: Player) -> u32 {
+        self.scores[player.index()]
+    }
+
+    /// Gets the number of ticks left in the round
+    pub fn get_ticks_remaining(&self) -> u32 {
+        self.ticks_remaining
+    }
+
+    /// Gets the event in progress, if any
+    pub fn get_active_event(&self) -> Option<BattleEvent> {
+        self.active_event.map(|(event, _)| event)
+    }
+
+    /// Advances the battle by a single simulation step, scoring any walkers that entered owned
+    /// rockets. Events end after EVENT_DURATION ticks, and every EVENT_INTERVAL ticks without an
+    /// event there is a chance of a random one starting. Once the round timer has run out the
+    /// battle no longer advances
+    ///
+    /// Return value:
+    /// The scores, any event that started, and the outcome once the round is over
+    pub fn tick(&mut self) -> BattleReport {
+        self.tick_with_events(&mut ())
+    }
+
+    /// Advances the battle as tick does, reporting everything that happens in the world to a
+    /// sink so that it can be animated or played as a sound
+    ///
+    /// Arguments:
+    /// * `events`: Receives each event as it happens
+    ///
+    /// Return value:
+    /// The scores, any event that started, and the outcome once the round is over
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Battle, Direction, WalkerType, World, WorldEvent};
+    /// let mut world = World::new();
+    /// world.create_walker(0, 0, Direction::Up, WalkerType::Mouse);
+    /// let mut battle = Battle::new(world, 3600);
+    /// let mut bounces = 0;
+    /// for _ in 0..60 {
+    ///     battle.tick_with_events(&mut |event| {
+    ///         if let WorldEvent::WallBounce { .. } = event {
+    ///             bounces += 1;
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn tick_with_events<S: EventSink>(&mut self, events: &mut S) -> BattleReport {
+        let mut started_event = None;
+        if self.ticks_remaining > 0 {
+            self.ticks_remaining -= 1;
+            started_event = self.update_events();
+
+            // Scores are kept from the events, which report every walker entering a rocket
+            let (rocket_owners, scores) = (&self.rocket_owners, &mut self.scores);
+            self.world.battle_step(&mut |world_event: WorldEvent| {
+                if let WorldEvent::Rocket { event, .. } = world_event {
+                    if let Some(owner) = rocket_owners[event.y * WIDTH + event.x] {
+                        let score = &mut scores[owner.index()];
+                        match event.kind {
+                            RocketEventKind::Rescue => *score += MOUSE_POINTS,
+                            RocketEventKind::Hit | RocketEventKind::Destroyed => {
+                                *score -= *score / CAT_PENALTY_DIVISOR
+                            }
+                        }
+                    }
+                }
+                events.push_event(world_event);
+            });
+        }
+
+        BattleReport {
+            started_event,
+            ..self.get_report()
+        }
+    }
+
+    /// Gets the scores, the event in progress, and the outcome once the round is over
+    pub fn get_report(&self) -> BattleReport {
+        BattleReport {
+            scores: self.scores,
+            ticks_remaining: self.ticks_remaining,
+            outcome: if self.ticks_remaining == 0 {
+                Some(self.get_outcome())
+            } else {
+                None
+            },
+            active_event: self.get_active_event(),
+            started_event: None,
+        }
+    }
+
+    /// Ends the event in progress once it has run its course, or rolls for a new event
+    ///
+    /// Return value:
+    /// The event that started, if any
+    fn update_events(&mut self) -> Option<BattleEvent> {
+        if let Some((event, ticks_left)) = self.active_event {
+            if ticks_left > 1 {
+                self.active_event = Some((event, ticks_left - 1));
+            } else {
+                self.end_event();
+            }
+            return None;
+        }
+
+        self.ticks_until_event -= 1;
+        if self.ticks_until_event > 0 {
+            return None;
+        }
+        self.ticks_until_event = EVENT_INTERVAL;
+
+        let rng = self.world.get_rng_mut();
+        if rng.next_below(EVENT_CHANCE) != 0 {
+            return None;
+        }
+        let event = BattleEvent::ALL[rng.next_below(BattleEvent::ALL.len() as u32) as usize];
+        self.start_event(event);
+        Some(event)
+    }
+
+    /// Applies the rule changes of an event
+    ///
+    /// Arguments:
+    /// * `event`: The event to start
+    fn start_event(&mut self, event: BattleEvent) {
+        self.normal_speeds = [
+            self.world.get_walker_speed(WalkerType::Mouse),
+            self.world.get_walker_speed(WalkerType::Cat),
+        ];
+
+        match event {
+            BattleEvent::MouseMania => self
+                .world
+                .set_spawn_flood(Some((WalkerType::Mouse, MOUSE_MANIA_INTERVAL))),
+            BattleEvent::CatMania => self
+                .world
+                .set_spawn_flood(Some((WalkerType::Cat, CAT_MANIA_INTERVAL))),
+            BattleEvent::SpeedUp | BattleEvent::SlowDown => {
+                for (walker_type, speed) in [WalkerType::Mouse, WalkerType::Cat]
+                    .into_iter()
+                    .zip(self.normal_speeds)
+                {
+                    // Speeds must stay within a square, however fast or slow the ruleset is
+                    let speed = speed.scaled() as i16;
+                    let speed = if event == BattleEvent::SpeedUp {
+                        (speed * 2).min(<FixedPoint>::FRACTIONAL_UNITS - 1)
+                    } else {
+                        (speed / 2).max(1)
+                    };
+                    self.world
+                        .set_walker_speed(walker_type, FixedPoint::new(0, speed));
+                }
+            }
+        }
+
+        self.active_event = Some((event, EVENT_DURATION));
+    }
+
+    /// Returns the rules to normal
+    fn end_event(&mut self) {
+        self.world.set_spawn_flood(None);
+        for (walker_type, speed) in [WalkerType::Mouse, WalkerType::Cat]
+            .into_iter()
+            .zip(self.normal_speeds)
+        {
+            self.world.set_walker_speed(walker_type, speed);
+        }
+
+        self.active_event = None;
+    }
+
+    /// Decides the outcome from the current scores
+    fn get_outcome(&self) -> BattleOutcome {
+        let best = self.scores.iter().copied().max().unwrap_or(0);
+        let mut leaders = Player::ALL
+            .iter()
+            .filter(|player| self.scores[player.index()] == best);
+
+        match (leaders.next(), leaders.next()) {
+            (Some(&player), None) => BattleOutcome::Winner(player),
+            _ => BattleOutcome::Draw,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Direction, RocketEvent, WalkerType, WorldEvent};
+    use arrayvec::ArrayVec;
+
+    /// GIVEN a mouse walking into a rocket
+    /// WHEN the battle is ticked with an event sink until the mouse arrives
+    /// THEN the rescue is reported to the sink
+    #[test]
+    fn tick_with_events_reports_world_events() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        let id = world.get_mice()[0].get_id();
+        let mut battle = Battle::new(world, 1000);
+
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+        for _ in 0..60 {
+            battle.tick_with_events(&mut events);
+        }
+
+        assert_eq!(
+            &[WorldEvent::Rocket {
+                id,
+                event: RocketEvent {
+                    x: 1,
+                    y: 0,
+                    kind: RocketEventKind::Rescue,
+                },
+            }],
+            events.as_slice()
+        );
+    }
+
+    /// GIVEN a mouse walking into a rocket owned by player two
+    /// WHEN the battle is ticked until the mouse arrives
+    /// THEN player two scores a point
+    #[test]
+    fn rescued_mice_score_for_owner() {
+        let mut world = World::new();
+        world.set_tile(2, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        let mut battle = Battle::new(world, 1000);
+        battle.set_rocket_owner(2, 0, Player::Two).unwrap();
+
+        for _ in 0..119 {
+            battle.tick();
+        }
+        assert_eq!(0, battle.get_score(Player::Two));
+
+        let report = battle.tick();
+        assert_eq!([0, 1, 0, 0], report.scores);
+        assert_eq!(None, report.outcome);
+    }
+
+    /// GIVEN a cat walking into a rocket owned by a player with 10 points
+    /// WHEN the cat arrives
+    /// THEN the rocket survives and a third of the score is lost
+    #[test]
+    fn cats_take_share_of_score() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        let mut battle = Battle::new(world, 1000);
+        battle.set_rocket_owner(1, 0, Player::One).unwrap();
+        battle.scores[Player::One.index()] = 10;
+
+        for _ in 0..90 {
+            battle.tick();
+        }
+
+        assert_eq!(7, battle.get_score(Player::One));
+        assert_eq!(TileType::Rocket, battle.get_world().get_arrow(1, 0));
+    }
+
+    /// GIVEN a mouse walking into an unowned rocket
+    /// WHEN the mouse arrives
+    /// THEN nobody scores
+    #[test]
+    fn unowned_rockets_do_not_score() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        let mut battle = Battle::new(world, 1000);
+
+        for _ in 0..60 {
+            battle.tick();
+        }
+
+        assert_eq!([0; MAX_PLAYERS], battle.get_report().scores);
+    }
+
+    /// GIVEN a battle with a short round
+    /// WHEN the round timer runs out
+    /// THEN the highest score wins, or the battle is drawn if the highest score is shared, and
+    /// further ticks have no effect
+    #[test]
+    fn round_timer_decides_winner() {
+        let mut battle = Battle::new(World::new(), 10);
+        battle.scores = [3, 5, 5, 0];
+        for _ in 0..9 {
+            assert_eq!(None, battle.tick().outcome);
+        }
+        assert_eq!(Some(BattleOutcome::Draw), battle.tick().outcome);
+
+        battle.scores = [3, 6, 5, 0];
+        let report = battle.tick();
+        assert_eq!(0, report.ticks_remaining);
+        assert_eq!(Some(BattleOutcome::Winner(Player::Two)), report.outcome);
+    }
+
+    /// GIVEN a world with a rocket
+    /// WHEN ownership of the rocket and of an empty tile is set
+    /// THEN only the rocket can be owned
+    #[test]
+    fn only_rockets_can_be_owned() {
+        let mut world = World::new();
+        world.set_tile(4, 4, TileType::Rocket);
+        let mut battle = Battle::new(world, 10);
+
+        assert_eq!(Ok(()), battle.set_rocket_owner(4, 4, Player::Three));
+        assert_eq!(Err(BattleError::NotARocket), battle.set_rocket_owner(5, 4, Player::Three));
+        assert_eq!(Some(Player::Three), battle.get_rocket_owner(4, 4));
+        assert_eq!(None, battle.get_rocket_owner(5, 4));
+    }
+
+    /// GIVEN a battle with a speed event started
+    /// WHEN the event runs its course
+    /// THEN walkers move twice or half as fast until the event ends
+    #[test]
+    fn speed_events_change_walker_speed() {
+        let mut battle = Battle::new(World::new(), 1000);
+
+        battle.start_event(BattleEvent::SpeedUp);
+        assert_eq!(
+            12,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Mouse)
+                .scaled()
+        );
+        assert_eq!(
+            8,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Cat)
+                .scaled()
+        );
+
+        battle.end_event();
+        battle.start_event(BattleEvent::SlowDown);
+        assert_eq!(
+            3,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Mouse)
+                .scaled()
+        );
+        assert_eq!(
+            2,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Cat)
+                .scaled()
+        );
+
+        for _ in 0..EVENT_DURATION - 1 {
+            assert_eq!(Some(BattleEvent::SlowDown), battle.tick().active_event);
+        }
+        assert_eq!(None, battle.tick().active_event);
+        assert_eq!(
+            6,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Mouse)
+                .scaled()
+        );
+        assert_eq!(
+            4,
+            battle
+                .get_world()
+                .get_walker_speed(WalkerType::Cat)
+                .scaled()
+        );
+    }
+
+    /// GIVEN a battle with a ruleset of custom speeds
+    /// WHEN a SpeedUp and then a SlowDown event run their course
+    /// THEN the custom speeds are scaled during each event and restored after it
+    #[test]
+    fn speed_events_keep_ruleset_speeds() {
+        let ruleset = Ruleset {
+            mouse_speed: <FixedPoint>::new(0, 10),
+            cat_speed: <FixedPoint>::new(0, 5),
+            ..Ruleset::battle()
+        };
+        let mut battle = Battle::with_ruleset(World::new(), 1000, ruleset);
+        let speeds = |battle: &Battle| {
+            let world = battle.get_world();
+            (
+                world.get_walker_speed(WalkerType::Mouse).scaled(),
+                world.get_walker_speed(WalkerType::Cat).scaled(),
+            )
+        };
+
+        battle.start_event(BattleEvent::SpeedUp);
+        assert_eq!((20, 10), speeds(&battle));
+        battle.end_event();
+        assert_eq!((10, 5), speeds(&battle));
+
+        battle.start_event(BattleEvent::SlowDown);
+        assert_eq!((5, 2), speeds(&battle));
+        for _ in 0..EVENT_DURATION {
+            battle.tick();
+        }
+        assert_eq!(None, battle.get_active_event());
+        assert_eq!((10, 5), speeds(&battle));
+        assert_eq!(ruleset, *battle.get_world().get_ruleset());
+    }
+
+    /// GIVEN a cat spawner next to an owned rocket
+    /// WHEN Mouse Mania starts
+    /// THEN the spawner creates mice, which score for the rocket owner
+    #[test]
+    fn mouse_mania_floods_spawners() {
+        let mut world = World::new();
+        world.create_spawner(0, 0, Direction::Right, WalkerType::Cat);
+        world.set_tile(1, 0, TileType::Rocket);
+        let mut battle = Battle::new(world, 1000);
+        battle.set_rocket_owner(1, 0, Player::One).unwrap();
+
+        battle.start_event(BattleEvent::MouseMania);
+        for _ in 0..MOUSE_MANIA_INTERVAL * 10 {
+            battle.tick();
+        }
+
+        assert!(battle.get_score(Player::One) > 0);
+    }
+
+    /// GIVEN two battles on identically seeded worlds
+    /// WHEN both are run for a long round
+    /// THEN the same events start at the same ticks, are reported, and leave normal rules behind
+    #[test]
+    fn events_are_repeatable() {
+        let mut events = [[None; 20], [None; 20]];
+        for run in events.iter_mut() {
+            let mut world = World::new();
+            world.set_seed(7);
+            let mut battle = Battle::new(world, EVENT_INTERVAL * 20);
+            let mut count = 0;
+            while battle.get_ticks_remaining() > 0 {
+                let report = battle.tick();
+                if let Some(event) = report.started_event {
+                    assert_eq!(Some(event), report.active_event);
+                    run[count] = Some((report.ticks_remaining, event));
+                    count += 1;
+                }
+            }
+
+            if battle.get_active_event().is_none() {
+                assert_eq!(
+                    6,
+                    battle
+                        .get_world()
+                        .get_walker_speed(WalkerType::Mouse)
+                        .scaled()
+                );
+            }
+        }
+
+        assert_eq!(events[0], events[1]);
+        assert!(events[0][0].is_some());
+    }
+}
@@ -0,0 +1,6 @@
+/// Reasons that changing a battle can fail
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BattleError {
+    /// Only rockets can be owned by a player
+    NotARocket,
+}
@@ -0,0 +1,22 @@
+/// Timed events that change the rules of a battle for everybody
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BattleEvent {
+    /// Every spawner rapidly creates mice
+    MouseMania,
+    /// Every spawner creates cats
+    CatMania,
+    /// Mice and cats move twice as fast
+    SpeedUp,
+    /// Mice and cats move half as fast
+    SlowDown,
+}
+
+impl BattleEvent {
+    /// Every event, in the order the random number generator picks from
+    pub const ALL: [BattleEvent; 4] = [
+        BattleEvent::MouseMania,
+        BattleEvent::CatMania,
+        BattleEvent::SpeedUp,
+        BattleEvent::SlowDown,
+    ];
+}
@@ -1,5 +1,5 @@
 /// Represents the four ordinal directions
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Direction {
     Up,
     Down,
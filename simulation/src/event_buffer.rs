@@ -0,0 +1,87 @@
+use crate::{EventSink, WorldEvent};
+use arrayvec::ArrayVec;
+
+/// Collects events into a bounded buffer, counting those that do not fit rather than dropping
+/// them silently. Fast forward runs several steps in each tick, so a buffer sized for one step
+/// can fill up. Check the dropped count to know whether every event was seen
+///
+/// #examples
+/// ```
+/// use simulation::{EventBuffer, World};
+/// let mut world = World::new();
+/// let mut events: EventBuffer<16> = EventBuffer::new();
+/// world.tick_with_events(&mut events);
+/// assert!(events.get_events().is_empty());
+/// assert_eq!(0, events.get_dropped());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventBuffer<const CAPACITY: usize> {
+    events: ArrayVec<WorldEvent, CAPACITY>,
+    /// The number of events that arrived while the buffer was full
+    dropped: usize,
+}
+
+impl<const CAPACITY: usize> EventBuffer<CAPACITY> {
+    /// Creates an empty buffer
+    pub fn new() -> Self {
+        EventBuffer {
+            events: ArrayVec::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Gets the events collected, in the order they happened
+    pub fn get_events(&self) -> &[WorldEvent] {
+        &self.events
+    }
+
+    /// Gets the number of events that were dropped because the buffer was full
+    pub fn get_dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Empties the buffer and resets the dropped count, ready for the next tick
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.dropped = 0;
+    }
+}
+
+impl<const CAPACITY: usize> EventSink for EventBuffer<CAPACITY> {
+    fn push_event(&mut self, event: WorldEvent) {
+        if self.events.try_push(event).is_err() {
+            self.dropped += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Direction, TileType, WalkerType, World};
+
+    /// GIVEN a buffer with room for one event
+    /// AND two mice that are rescued on the same step
+    /// WHEN the world is ticked until they are rescued
+    /// THEN the first rescue is kept, the second is counted as dropped, and clear resets both
+    #[test]
+    fn counts_dropped_events() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Rocket);
+        world.set_tile(1, 1, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 1, Direction::Right, WalkerType::Mouse);
+        world.start();
+        let mut events: EventBuffer<1> = EventBuffer::new();
+
+        for _ in 0..60 {
+            world.tick_with_events(&mut events);
+        }
+
+        assert_eq!(1, events.get_events().len());
+        assert_eq!(1, events.get_dropped());
+        events.clear();
+        assert!(events.get_events().is_empty());
+        assert_eq!(0, events.get_dropped());
+    }
+}
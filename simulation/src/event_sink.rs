@@ -0,0 +1,48 @@
+use crate::WorldEvent;
+use arrayvec::ArrayVec;
+
+/// Receives the events that happen while a world is ticked. Implemented for bounded buffers and
+/// closures, so that no allocation is required
+pub trait EventSink {
+    /// Receives an event
+    ///
+    /// Arguments:
+    /// * `event`: The event that happened
+    fn push_event(&mut self, event: WorldEvent);
+}
+
+/// Discards every event. Used when ticking without a sink
+impl EventSink for () {
+    fn push_event(&mut self, _event: WorldEvent) {}
+}
+
+/// Collects events into a bounded buffer. Events that do not fit are dropped without any signal,
+/// so size the buffer for every step of a tick, as fast forward runs several. Use an EventBuffer
+/// to count the events dropped
+/// #examples
+/// ```
+/// use simulation::{World, WorldEvent};
+/// use arrayvec::ArrayVec;
+/// let mut world = World::new();
+/// let mut events: ArrayVec<WorldEvent, 16> = ArrayVec::new();
+/// world.tick_with_events(&mut events);
+/// ```
+impl<const CAPACITY: usize> EventSink for ArrayVec<WorldEvent, CAPACITY> {
+    fn push_event(&mut self, event: WorldEvent) {
+        let _ = self.try_push(event);
+    }
+}
+
+/// Passes each event to a closure
+/// #examples
+/// ```
+/// use simulation::{World, WorldEvent};
+/// let mut world = World::new();
+/// let mut count = 0;
+/// world.tick_with_events(&mut |_event: WorldEvent| count += 1);
+/// ```
+impl<F: FnMut(WorldEvent)> EventSink for F {
+    fn push_event(&mut self, event: WorldEvent) {
+        self(event)
+    }
+}
@@ -1,53 +1,123 @@
-use core::ops::{Add, AddAssign, Sub, SubAssign};
-// TODO: Implement use std::cmp::{Ord, PartialOrd};
+use core::cmp::Ordering;
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// The number of fractional units in a whole used by the simulation. 360 divides evenly by the
+/// 60 and 90 steps mice and cats take to cross a square
+pub const DEFAULT_FRACTIONAL_UNITS: i16 = 360;
 
 /// Represents a fixed point datatype tailored to Chu-chu Rocket clones
 /// running at 60fps.
 /// We also want to run on microcontrollers, so we're restricting ourselves
 /// to 16 bit data types.
-/// It would be nicer to use a fractional part that aligned to a bit
-/// boundary so that we could do simple bitshifting, but c'est la vie.
-/// TODO: When const generics land, make the fractional part a generic parameter
-/// TODO: If I can't avoid division when mapping to pixels, consider just using i16 with a
-/// 360 scaling factor.
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub struct FixedPoint {
+/// The number of fractional units in a whole is a parameter. The simulation uses 360 so that
+/// walker speeds divide evenly, but a power of two such as 256 lets pixel mapping use shifts
+/// rather than division. A single scaled i16 is not used, as at 360 units it could not reach
+/// the far side of a world
+/// Values are always normalised so that the fractional part has the same sign as the integer
+/// part, which makes equivalent values equal. Both parts round towards zero, so 1.5 is stored as
+/// 1 + 180/360 and -1.5 as -1 - 180/360
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FixedPoint<const UNITS: i16 = DEFAULT_FRACTIONAL_UNITS> {
     value: i8,
     fractional: i16,
 }
 
-impl FixedPoint {
-    /// Creates a new fixed point value from the individual components
-    /// The input range of fractional should be in the range [-359..359]
-    /// TODO: Add assertions (or use BoundedInteger crate)
+impl<const UNITS: i16> FixedPoint<UNITS> {
+    /// The number of fractional units in a whole
+    pub const FRACTIONAL_UNITS: i16 = UNITS;
+    /// Evaluated by every constructor to reject resolutions that cannot be normalised in an i16
+    const UNITS_VALID: () = assert!(
+        UNITS > 1 && UNITS <= i16::MAX / 2,
+        "Fractional units must be in the range 2-16383"
+    );
+
+    /// Creates a new fixed point value from the individual components, normalising it
+    ///
+    /// Arguments:
+    /// * `value`: The integer part
+    /// * `fractional`: The fractional part, in fractional units. Must be in the range
+    ///   [1 - UNITS..UNITS - 1], which is [-359..359] by default
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// let a = <FixedPoint>::new(1,  180); // 1.5
+    /// let b = <FixedPoint>::new(1, -180); // 0.5
+    /// let c = <FixedPoint>::new(0,  180); // Also 0.5!
+    /// assert!(b == c);
+    /// ```
+    pub const fn new(value: i8, fractional: i16) -> Self {
+        assert!(fractional > -UNITS && fractional < UNITS);
+
+        Self::normalised(value, fractional)
+    }
+
+    /// Creates a new fixed point value from a whole value expressed in fractional units, as
+    /// returned by scaled
+    ///
+    /// Arguments:
+    /// * `scaled`: The value in fractional units. The integer part must fit in an i8
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::FixedPoint;
-    /// let a = FixedPoint::new(1,  180); // 1.5
-    /// let b = FixedPoint::new(1, -180); // 0.5
-    /// let c = FixedPoint::new(0,  180); // Also 0.5!
+    /// use simulation::FixedPoint;
+    /// assert!(<FixedPoint>::from_scaled(-540) == <FixedPoint>::new(-1, -180));
     /// ```
-    pub fn new(value: i8, fractional: i16) -> FixedPoint {
-        FixedPoint { value, fractional }
+    pub const fn from_scaled(scaled: i32) -> Self {
+        let () = Self::UNITS_VALID;
+
+        let value = scaled / UNITS as i32;
+        assert!(value >= i8::MIN as i32 && value <= i8::MAX as i32);
+
+        // Division and remainder both round towards zero, so the result is already normalised
+        Self {
+            value: value as i8,
+            fractional: (scaled % UNITS as i32) as i16,
+        }
+    }
+
+    /// Normalises the components of a value, carrying any whole part of the fractional part into
+    /// the integer part and giving both parts the same sign
+    ///
+    /// Arguments:
+    /// * `value`: The integer part
+    /// * `fractional`: The fractional part, in fractional units. Must be in the range
+    ///   [1 - 2 * UNITS..2 * UNITS - 1]
+    const fn normalised(mut value: i8, mut fractional: i16) -> Self {
+        let () = Self::UNITS_VALID;
+
+        if fractional >= UNITS {
+            value += 1;
+            fractional -= UNITS;
+        } else if fractional <= -UNITS {
+            value -= 1;
+            fractional += UNITS;
+        }
+
+        if value > 0 && fractional < 0 {
+            value -= 1;
+            fractional += UNITS;
+        } else if value < 0 && fractional > 0 {
+            value += 1;
+            fractional -= UNITS;
+        }
+
+        Self { value, fractional }
     }
 
     /// Creates a new fixed point value from a floating point value
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::FixedPoint;
-    /// let a = FixedPoint::from_float(0.5f32);
+    /// use simulation::FixedPoint;
+    /// let a = <FixedPoint>::from_float(0.5f32);
     /// ```
-    pub fn from_float(value: f32) -> FixedPoint {
+    pub fn from_float(value: f32) -> Self {
         let integral_part = value as i8;
-        // no_std means I can't use f32.trunc(), so instead we convert integral_part back to f32
+        // no_std means I can't use f32.trunc(), so instead we convert integral_part back to f32.
+        // The remainder has the same sign as the integral part
         let remainder = value - integral_part as f32;
-        if remainder > 0f32 {
-            FixedPoint::new(integral_part, 0) + FixedPoint::new(0, (remainder * 360.0f32) as i16)
-        } else {
-            FixedPoint::new(integral_part, 0) - FixedPoint::new(0, (remainder * 360.0f32) as i16)
-        }
+        Self::new(integral_part, (remainder * UNITS as f32) as i16)
     }
 
     /// Returns true if the change between two FixedPoint values results in a new
@@ -55,9 +125,9 @@ impl FixedPoint {
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::FixedPoint;
-    /// let a = FixedPoint::new(1, 359);
-    /// let b = FixedPoint::new(2, 0);
+    /// use simulation::FixedPoint;
+    /// let a = <FixedPoint>::new(1, 359);
+    /// let b = <FixedPoint>::new(2, 0);
     ///
     /// assert_eq!(b.did_overflow(a), true);
     /// ```
@@ -73,30 +143,48 @@ impl FixedPoint {
     /// * `to_min`: The value that will be returned given an input of from_min
     /// * `to_max`: The value that will be returned given an input of from_max
     /// * `from_min`: The input mapping minimum
-    /// * `from_max`: The input mapping maximum
+    /// * `from_max`: The input mapping maximum. Must differ from from_min
     ///
-    /// TODO: Add some assertions about intputs
-    pub fn map_to_i16(
-        self,
-        from_min: FixedPoint,
-        from_max: FixedPoint,
-        to_min: i16,
-        to_max: i16,
-    ) -> i16 {
-        // Integer components are worth 360 times that of the fractional ones, which requires
-        // an additional 9 bits. We therefore need to use an i32 to accurately represent this
+    /// Input ranges spanning a power of two fractional units, such as 16 whole units of a
+    /// FixedPoint<256>, are mapped with a shift rather than the i32 divide. Both round towards
+    /// zero, so the result does not depend on which is used
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// let from_min = FixedPoint::<256>::new(0, 0);
+    /// let from_max = FixedPoint::<256>::new(16, 0);
+    /// assert_eq!(18, FixedPoint::<256>::new(1, 128).map_to_i16(from_min, from_max, 0, 192));
+    /// assert_eq!(-6, FixedPoint::<256>::new(0, -128).map_to_i16(from_min, from_max, 0, 192));
+    /// ```
+    pub fn map_to_i16(self, from_min: Self, from_max: Self, to_min: i16, to_max: i16) -> i16 {
+        // Integer components are worth 360 times that of the fractional ones by default, which
+        // requires an additional 9 bits. We therefore need to use an i32 to accurately represent this
         // If we restrict the input range, we can probably do this in an i16
         let to_delta = (to_max - to_min) as i32;
-        let from_min_scaled = from_min.fractional as i32 + from_min.value as i32 * 360;
-        let from_max_scaled = from_max.fractional as i32 + from_max.value as i32 * 360;
+        let from_min_scaled = from_min.scaled();
+        let from_max_scaled = from_max.scaled();
         let from_delta_scaled = from_max_scaled - from_min_scaled;
-        let self_scaled = self.fractional as i32 + self.value as i32 * 360;
+        assert!(from_delta_scaled != 0, "The input mapping range must not be empty");
+        let self_scaled = self.scaled();
 
-        // Now everthing is in 360ths, perform the mapping
+        // Now everthing is in fractional units, perform the mapping
         // I use 17 bits to represent the scaled variables
         // I use 16 bits to represent the to/from
         // We're two bits short, so we should be careful with input ranges!
-        (to_min as i32 + ((self_scaled - from_min_scaled) * to_delta) / from_delta_scaled) as i16
+        let offset = (self_scaled - from_min_scaled) * to_delta;
+        let mapped = if from_delta_scaled > 0 && from_delta_scaled.count_ones() == 1 {
+            // Shifting rounds down, so shift the magnitude to round towards zero like division
+            let shift = from_delta_scaled.trailing_zeros();
+            if offset < 0 {
+                -(-offset >> shift)
+            } else {
+                offset >> shift
+            }
+        } else {
+            offset / from_delta_scaled
+        };
+        (to_min as i32 + mapped) as i16
 
         // TBD:
         // Given I will be displaying on a 160x120 screen and I want a 12x9 grid, that means each
@@ -111,15 +199,141 @@ impl FixedPoint {
     pub fn integer_part(self) -> i8 {
         self.value
     }
+
+    /// Gets the largest whole number not greater than the number
+    /// 1.5 -> 1
+    /// -1.5 -> -2
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// assert_eq!(-1, <FixedPoint>::new(0, -180).floor());
+    /// assert_eq!(0, <FixedPoint>::new(0, 180).floor());
+    /// ```
+    pub fn floor(self) -> i8 {
+        if self.fractional < 0 {
+            self.value - 1
+        } else {
+            self.value
+        }
+    }
+
+    /// Gets the fractional part of the number, in fractional units
+    /// 1.5 -> 180
+    /// -1.5 -> -180
+    pub fn fractional_part(self) -> i16 {
+        self.fractional
+    }
+
+    /// Gets the whole value expressed in fractional units. Integer components are worth UNITS
+    /// fractional units, so this needs an i32 to be represented accurately
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// assert_eq!(540, <FixedPoint>::new(1, 180).scaled());
+    /// assert_eq!(180, <FixedPoint>::new(1, -180).scaled());
+    /// ```
+    pub fn scaled(self) -> i32 {
+        self.fractional as i32 + self.value as i32 * UNITS as i32
+    }
+
+    /// Gets the absolute value
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// assert!(<FixedPoint>::new(-1, -180).abs() == <FixedPoint>::new(1, 180));
+    /// ```
+    pub fn abs(self) -> Self {
+        if self.value < 0 || self.fractional < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Gets the absolute distance between two values
+    ///
+    /// Arguments:
+    /// * `other`: The value to measure the distance to
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::FixedPoint;
+    /// let a = <FixedPoint>::new(1, 0);
+    /// let b = <FixedPoint>::new(2, 180);
+    /// assert!(a.abs_diff(b) == <FixedPoint>::new(1, 180));
+    /// assert!(b.abs_diff(a) == <FixedPoint>::new(1, 180));
+    /// ```
+    pub fn abs_diff(self, other: Self) -> Self {
+        (self - other).abs()
+    }
+}
+
+/// Implements the Ord trait for FixedPoint, ordering by value
+/// #examples
+/// ```
+/// use simulation::FixedPoint;
+/// assert!(<FixedPoint>::new(0, -180) < <FixedPoint>::new(0, 180));
+/// assert!(<FixedPoint>::new(-1, 0) < <FixedPoint>::new(0, -180));
+/// ```
+impl<const UNITS: i16> Ord for FixedPoint<UNITS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.scaled().cmp(&other.scaled())
+    }
+}
+
+/// Implements the PartialOrd trait for FixedPoint, consistent with Ord
+impl<const UNITS: i16> PartialOrd for FixedPoint<UNITS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Implements the Neg trait for FixedPoint. Panics when negating a value with an integer part of
+/// i8::MIN, as the result does not fit
+/// #examples
+/// ```
+/// use simulation::FixedPoint;
+/// assert!(-<FixedPoint>::new(1, 180) == <FixedPoint>::new(-1, -180));
+/// ```
+impl<const UNITS: i16> Neg for FixedPoint<UNITS> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            value: self
+                .value
+                .checked_neg()
+                .expect("negated integer part must fit in an i8"),
+            fractional: -self.fractional,
+        }
+    }
+}
+
+/// Implements multiplication by an integer for FixedPoint. The integer part of the result must
+/// fit in an i8
+/// #examples
+/// ```
+/// use simulation::FixedPoint;
+/// assert!(<FixedPoint>::new(0, 240) * 3 == <FixedPoint>::new(2, 0));
+/// ```
+impl<const UNITS: i16> Mul<i16> for FixedPoint<UNITS> {
+    type Output = Self;
+
+    fn mul(self, other: i16) -> Self {
+        Self::from_scaled(self.scaled() * other as i32)
+    }
 }
 
 /// Implements the Add trait for FixedPoint
 /// #examples
 /// ```
-/// use shoko_rocket_rust::FixedPoint;
-/// FixedPoint::new(1, 0) + FixedPoint::new(1, 0);
+/// use simulation::FixedPoint;
+/// <FixedPoint>::new(1, 0) + <FixedPoint>::new(1, 0);
 /// ```
-impl Add for FixedPoint {
+impl<const UNITS: i16> Add for FixedPoint<UNITS> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
@@ -132,33 +346,26 @@ impl Add for FixedPoint {
 /// Implements the AddAssign trait for FixedPoint
 /// #examples
 /// ```
-/// use shoko_rocket_rust::FixedPoint;
-/// let mut a = FixedPoint::new(1, 0);
-/// let b = FixedPoint::new(1, 0);
+/// use simulation::FixedPoint;
+/// let mut a = <FixedPoint>::new(1, 0);
+/// let b = <FixedPoint>::new(1, 0);
 /// a += b;
 /// ```
-impl AddAssign for FixedPoint {
+impl<const UNITS: i16> AddAssign for FixedPoint<UNITS> {
     fn add_assign(&mut self, other: Self) {
-        // Add fractional parts. Assume they are in a valid range
-        let mut fractional_sum = self.fractional + other.fractional;
-        let mut integral_sum = self.value + other.value;
-        if fractional_sum >= 360 {
-            integral_sum += 1;
-            fractional_sum -= 360;
-        }
-
-        self.value = integral_sum;
-        self.fractional = fractional_sum;
+        // Add the parts separately. Both fractional parts are in range, so the sum needs at most
+        // a single carry
+        *self = Self::normalised(self.value + other.value, self.fractional + other.fractional);
     }
 }
 
 /// Implements the Sub trait for FixedPoint
 /// #examples
 /// ```
-/// use shoko_rocket_rust::FixedPoint;
-/// FixedPoint::new(1, 0) - FixedPoint::new(1, 0);
+/// use simulation::FixedPoint;
+/// <FixedPoint>::new(1, 0) - <FixedPoint>::new(1, 0);
 /// ```
-impl Sub for FixedPoint {
+impl<const UNITS: i16> Sub for FixedPoint<UNITS> {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
@@ -171,23 +378,16 @@ impl Sub for FixedPoint {
 /// Implements the SubAssign trait for FixedPoint
 /// #examples
 /// ```
-/// use shoko_rocket_rust::FixedPoint;
-/// let mut a = FixedPoint::new(1, 0);
-/// let b = FixedPoint::new(1, 0);
+/// use simulation::FixedPoint;
+/// let mut a = <FixedPoint>::new(1, 0);
+/// let b = <FixedPoint>::new(1, 0);
 /// a -= b;
 /// ```
-impl SubAssign for FixedPoint {
+impl<const UNITS: i16> SubAssign for FixedPoint<UNITS> {
     fn sub_assign(&mut self, other: Self) {
-        // Subtract fractional parts. Assume they are in a valid range
-        let mut fractional_sum = self.fractional - other.fractional;
-        let mut integral_sum = self.value - other.value;
-        if fractional_sum <= -360 {
-            integral_sum -= 1;
-            fractional_sum += 360;
-        }
-
-        self.value = integral_sum;
-        self.fractional = fractional_sum;
+        // Subtract the parts separately. Both fractional parts are in range, so the difference
+        // needs at most a single borrow
+        *self = Self::normalised(self.value - other.value, self.fractional - other.fractional);
     }
 }
 
@@ -200,8 +400,8 @@ mod tests {
     /// THEN The result is 1
     #[test]
     fn zero_plus_one() {
-        let zero = FixedPoint::new(0, 0);
-        let one = FixedPoint::new(1, 0);
+        let zero = <FixedPoint>::new(0, 0);
+        let one = <FixedPoint>::new(1, 0);
 
         let sum = zero + one;
         assert_eq!(sum.value, 1i8);
@@ -213,8 +413,8 @@ mod tests {
     /// THEN The result is 2 + 12/360
     #[test]
     fn repeated_small_addition() {
-        let mut sum = FixedPoint::new(0, 0);
-        let step = FixedPoint::new(0, 1);
+        let mut sum = <FixedPoint>::new(0, 0);
+        let step = <FixedPoint>::new(0, 1);
 
         for _x in 0..732 {
             sum += step;
@@ -229,8 +429,8 @@ mod tests {
     /// THEN The result is 8 + 1/6
     #[test]
     fn fractional_overflow_on_addition() {
-        let a = FixedPoint::new(3, 180);
-        let b = FixedPoint::new(4, 240);
+        let a = <FixedPoint>::new(3, 180);
+        let b = <FixedPoint>::new(4, 240);
         let sum = a + b;
 
         assert_eq!(sum.value, 8i8);
@@ -239,11 +439,11 @@ mod tests {
 
     /// GIVEN A starting value of 0
     /// WHEN The minimum fractional part is subtracted 732 times
-    /// THEN The result is -3 + 348/360
+    /// THEN The result is -2 - 12/360
     #[test]
     fn repeated_small_subtraction() {
-        let mut sum = FixedPoint::new(0, 0);
-        let step = FixedPoint::new(0, 1);
+        let mut sum = <FixedPoint>::new(0, 0);
+        let step = <FixedPoint>::new(0, 1);
 
         for _x in 0..732 {
             sum -= step;
@@ -255,17 +455,102 @@ mod tests {
 
     /// GIVEN Two FixedPoint values of 3.5 and 4 + 2/3
     /// WHEN The two are subtracted
-    /// THEN The result is -2 + 5/6
+    /// THEN The result is -1 - 1/6
     #[test]
     fn fractional_underflow_on_subtraction() {
-        let a = FixedPoint::new(3, 180);
-        let b = FixedPoint::new(4, 240);
+        let a = <FixedPoint>::new(3, 180);
+        let b = <FixedPoint>::new(4, 240);
         let sum = a - b;
 
         assert_eq!(sum.value, -1i8);
         assert_eq!(sum.fractional, -60i16);
     }
 
+    /// GIVEN values whose integer and fractional parts have different signs
+    /// WHEN they are created
+    /// THEN they are normalised, so that they equal the equivalent values
+    #[test]
+    fn equivalent_values_are_equal() {
+        assert_eq!(<FixedPoint>::new(0, 180), <FixedPoint>::new(1, -180));
+        assert_eq!(<FixedPoint>::new(-1, -180), <FixedPoint>::new(-2, 180));
+        assert_eq!(<FixedPoint>::new(0, 0), <FixedPoint>::new(1, -180) - <FixedPoint>::new(0, 180));
+        assert_eq!(<FixedPoint>::new(-1, 0), <FixedPoint>::new(0, -359) + <FixedPoint>::new(0, -1));
+        assert_eq!(<FixedPoint>::new(0, -240), <FixedPoint>::new(1, 0) - <FixedPoint>::new(1, 240));
+    }
+
+    /// GIVEN a fractional part of a whole unit
+    /// WHEN a value is created from it
+    /// THEN the range assertion fails
+    #[test]
+    #[should_panic]
+    fn fractional_part_out_of_range() {
+        <FixedPoint>::new(0, 360);
+    }
+
+    /// GIVEN the most negative integer part
+    /// WHEN it is negated
+    /// THEN the result does not fit and the negation panics
+    #[test]
+    #[should_panic]
+    fn negation_overflow() {
+        let _ = -<FixedPoint>::new(i8::MIN, 0);
+    }
+
+    /// GIVEN values either side of zero
+    /// WHEN they are compared, negated, multiplied and measured
+    /// THEN the results are consistent with their scaled values
+    #[test]
+    fn ordering_and_arithmetic() {
+        let a = <FixedPoint>::new(0, -180);
+        let b = <FixedPoint>::new(1, 60);
+
+        assert!(a < b);
+        assert!(-b < a);
+        assert_eq!(b, a.max(b));
+        assert_eq!(<FixedPoint>::new(-1, -60), -b);
+        assert_eq!(<FixedPoint>::new(-1, -180), a * 3);
+        assert_eq!(<FixedPoint>::new(-2, -120), b * -2);
+        assert_eq!(<FixedPoint>::new(1, 240), a.abs_diff(b));
+        assert_eq!(<FixedPoint>::new(0, 180), a.abs());
+    }
+
+    /// GIVEN negative and positive floating point values
+    /// WHEN they are converted
+    /// THEN the sign of the fractional part is kept
+    #[test]
+    fn from_float_keeps_sign() {
+        assert_eq!(<FixedPoint>::new(0, -180), <FixedPoint>::from_float(-0.5));
+        assert_eq!(<FixedPoint>::new(-1, -90), <FixedPoint>::from_float(-1.25));
+        assert_eq!(<FixedPoint>::new(2, 90), <FixedPoint>::from_float(2.25));
+    }
+
+    /// GIVEN a power of two resolution
+    /// WHEN values are created, added and mapped to pixels
+    /// THEN they carry and normalise at the resolution, and map to the same pixels as the
+    /// default resolution
+    #[test]
+    fn power_of_two_resolution() {
+        let half = FixedPoint::<256>::new(0, 128);
+
+        assert_eq!(FixedPoint::<256>::new(1, 0), half + half);
+        assert_eq!(FixedPoint::<256>::new(0, 128), FixedPoint::<256>::new(1, -128));
+        assert_eq!(384, (half * 3).scaled());
+
+        for (value, fractional) in [(0, 0), (1, 180), (-2, -90), (11, 359)] {
+            let default = <FixedPoint>::new(value, fractional);
+            let power_of_two = FixedPoint::<256>::from_scaled(default.scaled() * 256 / 360);
+            assert_eq!(
+                default.map_to_i16(<FixedPoint>::new(0, 0), <FixedPoint>::new(16, 0), 0, 192),
+                power_of_two.map_to_i16(
+                    FixedPoint::<256>::new(0, 0),
+                    FixedPoint::<256>::new(16, 0),
+                    0,
+                    192
+                )
+            );
+        }
+    }
+
     /// GIVEN An initial staring position at zero
     /// WHEN Moved 50% of the way to the next whole value
     /// THEN did_overflow returns false
@@ -273,8 +558,8 @@ mod tests {
     /// THEN did_overflow returns true
     #[test]
     fn did_overflow() {
-        let start = FixedPoint::new(0, 0);
-        let half = FixedPoint::new(0, 180);
+        let start = <FixedPoint>::new(0, 0);
+        let half = <FixedPoint>::new(0, 180);
 
         let add_half = start + half;
         let add_full = start + half + half;
@@ -295,8 +580,8 @@ mod tests {
     /// THEN 0 is output
     #[test]
     fn map_to_i16_at_min() {
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(12, 0);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(12, 0);
         let to_min = 0;
         let to_max = 160;
         let output = from_min.map_to_i16(from_min, from_max, to_min, to_max);
@@ -310,8 +595,8 @@ mod tests {
     /// THEN 160 is output
     #[test]
     fn map_to_i16_at_max() {
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(12, 0);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(12, 0);
         let to_min = 0;
         let to_max = 160;
         let output = from_max.map_to_i16(from_min, from_max, to_min, to_max);
@@ -325,9 +610,9 @@ mod tests {
     /// THEN 80 is output
     #[test]
     fn map_to_i16_in_middle() {
-        let input = FixedPoint::new(6, 0);
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(12, 0);
+        let input = <FixedPoint>::new(6, 0);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(12, 0);
         let to_min = 0;
         let to_max = 160;
         let output = input.map_to_i16(from_min, from_max, to_min, to_max);
@@ -335,15 +620,26 @@ mod tests {
         assert_eq!(80, output);
     }
 
+    /// GIVEN equivalent values with different integral and fractional parts
+    /// WHEN they are scaled
+    /// THEN the same number of 360ths is returned
+    #[test]
+    fn scaled_equivalent_values() {
+        assert_eq!(180, <FixedPoint>::new(0, 180).scaled());
+        assert_eq!(180, <FixedPoint>::new(1, -180).scaled());
+        assert_eq!(-540, <FixedPoint>::new(-1, -180).scaled());
+        assert_eq!(-540, <FixedPoint>::new(-2, 180).scaled());
+    }
+
     /// GIVEN an input mapping range of 0..1
     /// GIVEN an output mapping range of 0..100
     /// WHEN -0.5 is mapped
     /// THEN -50 is output
     #[test]
     fn map_to_i16_below_min() {
-        let input = FixedPoint::new(0, -180);
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(1, 0);
+        let input = <FixedPoint>::new(0, -180);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(1, 0);
         let to_min = 0;
         let to_max = 100;
         let output = input.map_to_i16(from_min, from_max, to_min, to_max);
@@ -357,9 +653,9 @@ mod tests {
     /// THEN 150 is output
     #[test]
     fn map_to_i16_above_max() {
-        let input = FixedPoint::new(1, 180);
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(1, 0);
+        let input = <FixedPoint>::new(1, 180);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(1, 0);
         let to_min = 0;
         let to_max = 100;
         let output = input.map_to_i16(from_min, from_max, to_min, to_max);
@@ -367,22 +663,51 @@ mod tests {
         assert_eq!(150, output);
     }
 
+    /// GIVEN an input mapping range that starts and ends at the same value
+    /// WHEN a value is mapped
+    /// THEN the mapping panics
+    #[test]
+    #[should_panic]
+    fn map_to_i16_empty_range() {
+        let one = <FixedPoint>::new(1, 0);
+        let _ = one.map_to_i16(one, one, 0, 100);
+    }
+
+    /// GIVEN a power of two resolution
+    /// AND an input mapping range of 0..1, which is mapped with a shift
+    /// AND an output mapping range of 0..100
+    /// WHEN values either side of 0 are mapped
+    /// THEN they are rounded towards zero, as the divide would
+    #[test]
+    fn map_to_i16_shift_rounds_towards_zero() {
+        let from_min = FixedPoint::<256>::new(0, 0);
+        let from_max = FixedPoint::<256>::new(1, 0);
+        let map = |value: FixedPoint<256>| value.map_to_i16(from_min, from_max, 0, 100);
+
+        assert_eq!(-50, map(FixedPoint::<256>::new(0, -128)));
+        assert_eq!(0, map(FixedPoint::<256>::new(0, -1)));
+        assert_eq!(0, map(FixedPoint::<256>::new(0, 1)));
+        assert_eq!(-1, map(FixedPoint::<256>::new(0, -3)));
+        assert_eq!(1, map(FixedPoint::<256>::new(0, 3)));
+        assert_eq!(150, map(FixedPoint::<256>::new(1, 128)));
+    }
+
     /// GIVEN an input mapping range of 0..100
     /// GIVEN an output mapping range of 0..10
     /// WHEN [-10, 0, 50, 100, 110] are mapped
     /// THEN [-1, 0, 5, 10, 11] are output
     #[test]
     fn map_to_i16_over_small_range() {
-        let from_min = FixedPoint::new(0, 0);
-        let from_max = FixedPoint::new(100, 0);
+        let from_min = <FixedPoint>::new(0, 0);
+        let from_max = <FixedPoint>::new(100, 0);
         let to_min = 0;
         let to_max = 10;
 
-        assert_eq!(-1, FixedPoint::new(-10, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(0, FixedPoint::new(0, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(5, FixedPoint::new(50, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(10, FixedPoint::new(100, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(11, FixedPoint::new(110, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(-1, <FixedPoint>::new(-10, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(0, <FixedPoint>::new(0, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(5, <FixedPoint>::new(50, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(10, <FixedPoint>::new(100, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(11, <FixedPoint>::new(110, 0).map_to_i16(from_min, from_max, to_min, to_max));
     }
 
     /// GIVEN an input mapping range of 10..110
@@ -391,13 +716,13 @@ mod tests {
     /// THEN [100, 550, 1000] are output
     #[test]
     fn map_to_i16_with_non_zero_input_min() {
-        let from_min = FixedPoint::new(10, 0);
-        let from_max = FixedPoint::new(110, 0);
+        let from_min = <FixedPoint>::new(10, 0);
+        let from_max = <FixedPoint>::new(110, 0);
         let to_min = 100;
         let to_max = 1000;
 
-        assert_eq!(100, FixedPoint::new(10, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(550, FixedPoint::new(60, 0).map_to_i16(from_min, from_max, to_min, to_max));
-        assert_eq!(1000, FixedPoint::new(110, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(100, <FixedPoint>::new(10, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(550, <FixedPoint>::new(60, 0).map_to_i16(from_min, from_max, to_min, to_max));
+        assert_eq!(1000, <FixedPoint>::new(110, 0).map_to_i16(from_min, from_max, to_min, to_max));
     }
 }
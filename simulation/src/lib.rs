@@ -1,19 +1,63 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 mod arrow_stock;
+mod battle;
+mod battle_error;
+mod battle_event;
 mod direction;
+mod event_buffer;
+mod event_sink;
 mod fixed_point;
+mod loop_detector;
+mod owned_arrow;
+mod placement_error;
+mod player;
+mod replay;
+mod replay_error;
+mod rng;
+mod rocket;
+mod ruleset;
+mod snapshot;
+mod snapshot_error;
+#[cfg(feature = "std")]
+mod solver;
+mod spawner;
 mod state_machine;
 mod tile_type;
+mod verifier;
 mod walker;
+mod walker_counts;
 mod world;
+mod world_event;
 mod world_state;
 
 pub use arrow_stock::ArrowStock;
+pub use battle::{Battle, BattleOutcome, BattleReport, BATTLE_ARROW_LIFETIME, BATTLE_MAX_ARROWS};
+pub use battle_error::BattleError;
+pub use battle_event::BattleEvent;
 pub use direction::Direction;
-pub use fixed_point::FixedPoint;
+pub use event_buffer::EventBuffer;
+pub use event_sink::EventSink;
+pub use fixed_point::{FixedPoint, DEFAULT_FRACTIONAL_UNITS};
+pub use placement_error::PlacementError;
+pub use player::{Player, MAX_PLAYERS};
+pub use replay::{ReplayHeader, ReplayPlayer, ReplayRecorder, REPLAY_VERSION};
+pub use replay_error::ReplayError;
+pub use rng::Rng;
+pub use rocket::{RocketEvent, RocketEventKind, RocketPenalty};
+pub use ruleset::{DiminishPolicy, LossCondition, Ruleset, Turn, WinCondition};
+pub use snapshot::SNAPSHOT_VERSION;
+pub use snapshot_error::SnapshotError;
+#[cfg(feature = "std")]
+pub use solver::{
+    find_all_solutions, find_first_solution, PlacedArrow, Solution, SolverBudget, SolverReport,
+};
+pub use spawner::{Spawner, DEFAULT_SPAWN_INTERVAL};
 pub use state_machine::{GameState, StateMachine};
 pub use tile_type::TileType;
-pub use walker::{Walker, WalkerState, WalkerType};
-pub use world::World;
+pub use verifier::{verify_solution, Verification, VerificationOutcome};
+pub use walker::{Walker, WalkerId, WalkerState, WalkerType};
+pub use walker_counts::WalkerCounts;
+pub use world::{world_data_size, World, SNAPSHOT_MAX_SIZE};
+pub use world_event::{DeathCause, WorldEvent};
 pub use world_state::{WorldState, WorldStateChange};
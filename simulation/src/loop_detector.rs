@@ -0,0 +1,129 @@
+/// The FNV-1a offset basis
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+/// The FNV-1a prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Hashes the configuration of a world using 64 bit FNV-1a, which needs no allocation and gives
+/// the same hash on every platform
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct StateHasher {
+    hash: u64,
+}
+
+impl StateHasher {
+    /// Creates a hasher that has not hashed anything
+    pub(crate) fn new() -> StateHasher {
+        StateHasher {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.hash ^= value as u64;
+        self.hash = self.hash.wrapping_mul(FNV_PRIME);
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) {
+        for byte in value.to_le_bytes() {
+            self.write_u8(byte);
+        }
+    }
+
+    /// Gets the hash of everything written
+    pub(crate) fn finish(self) -> u64 {
+        self.hash
+    }
+}
+
+/// Detects a world returning to a configuration it has been in before, using Brent's cycle
+/// detection algorithm. Only one hash is stored, which is replaced each time the number of
+/// configurations seen since it was stored reaches a power of two. Once that power of two is
+/// longer than the loop, the stored configuration is seen again within one pass of the loop
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct LoopDetector {
+    /// The hash of the stored configuration
+    pub(crate) saved: Option<u64>,
+    /// The number of configurations to compare against the stored one before replacing it
+    pub(crate) power: u32,
+    /// The number of configurations compared against the stored one
+    pub(crate) length: u32,
+}
+
+impl LoopDetector {
+    /// Creates a detector that has not seen any configurations
+    pub(crate) fn new() -> LoopDetector {
+        LoopDetector {
+            saved: None,
+            power: 1,
+            length: 0,
+        }
+    }
+
+    /// Records a configuration of the world
+    ///
+    /// Arguments:
+    /// * `hash`: The hash of the configuration
+    ///
+    /// Return value:
+    /// True if the configuration has been seen before, so the world is in a loop
+    pub(crate) fn observe(&mut self, hash: u64) -> bool {
+        if self.saved == Some(hash) {
+            return true;
+        }
+
+        self.length += 1;
+        if self.saved.is_none() || self.length >= self.power {
+            self.saved = Some(hash);
+            self.power = self.power.saturating_mul(2);
+            self.length = 0;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// GIVEN a sequence of configurations that enters a loop of length 3 after 5 configurations
+    /// WHEN each is observed
+    /// THEN the loop is detected once the stored configuration comes around again
+    #[test]
+    fn detects_loop_after_lead_in() {
+        let mut detector = LoopDetector::new();
+        let sequence = (0..5).chain((0..).map(|index| 100 + index % 3));
+
+        let detected_at = sequence
+            .take(100)
+            .position(|hash| detector.observe(hash))
+            .unwrap();
+
+        // The configuration stored at the seventh observation is seen again one loop later
+        assert_eq!(9, detected_at);
+    }
+
+    /// GIVEN a sequence of configurations that never repeats
+    /// WHEN each is observed
+    /// THEN no loop is detected
+    #[test]
+    fn no_loop_without_repeats() {
+        let mut detector = LoopDetector::new();
+
+        assert!((0..1000).all(|hash| !detector.observe(hash)));
+    }
+
+    /// GIVEN two hashers
+    /// WHEN the same values are written in a different order
+    /// THEN the hashes differ
+    #[test]
+    fn hash_depends_on_order() {
+        let mut first = StateHasher::new();
+        first.write_u8(1);
+        first.write_u32(2);
+        let mut second = StateHasher::new();
+        second.write_u32(2);
+        second.write_u8(1);
+
+        assert_ne!(first.finish(), second.finish());
+    }
+}
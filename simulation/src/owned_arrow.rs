@@ -0,0 +1,13 @@
+use crate::Player;
+
+/// Tracks an arrow placed by a player during a battle. These arrows expire, and each player may
+/// only have a limited number on the board
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct OwnedArrow {
+    /// The player that placed the arrow
+    pub(crate) owner: Player,
+    /// The number of steps until the arrow is removed
+    pub(crate) ticks_remaining: u32,
+    /// Increases with each arrow placed, so that the oldest arrow can be found
+    pub(crate) sequence: u32,
+}
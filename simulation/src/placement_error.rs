@@ -0,0 +1,12 @@
+/// Reasons that placing or removing an arrow can fail
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PlacementError {
+    /// The tile contains a rocket or hole, so cannot hold an arrow
+    TileOccupied,
+    /// There are no arrows of the requested direction left in stock
+    OutOfStock,
+    /// There is no arrow on the tile to remove
+    NoArrow,
+    /// Arrows can only be changed while the world is stopped
+    NotStopped,
+}
@@ -0,0 +1,31 @@
+/// The largest number of players in a battle
+pub const MAX_PLAYERS: usize = 4;
+
+/// A player in a battle
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Player {
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+impl Player {
+    /// Every player, in order
+    pub const ALL: [Player; MAX_PLAYERS] = [Player::One, Player::Two, Player::Three, Player::Four];
+
+    /// Gets the index of the player, for indexing per-player arrays
+    /// #examples
+    /// ```
+    /// use simulation::Player;
+    /// assert_eq!(2, Player::Three.index());
+    /// ```
+    pub fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+            Player::Three => 2,
+            Player::Four => 3,
+        }
+    }
+}
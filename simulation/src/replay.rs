@@ -0,0 +1,578 @@
+use crate::world::{MAP_AUTHOR_OFFSET, MAP_AUTHOR_SIZE, MAP_NAME_OFFSET, MAP_NAME_SIZE};
+use crate::{ReplayError, StateMachine};
+use common::input::{ButtonState, InputState};
+
+/// The bytes every replay starts with
+const REPLAY_MAGIC: [u8; 4] = *b"SRRP";
+/// The version of the replay format written by this build
+pub const REPLAY_VERSION: u8 = 1;
+/// The size of the serialised header: magic, version, map name, map author and map checksum
+const HEADER_SIZE: usize = REPLAY_MAGIC.len() + 1 + MAP_NAME_SIZE + MAP_AUTHOR_SIZE + 4;
+
+/// A frame tag with this bit set repeats the previous input for the number of frames in the
+/// lower bits
+const TAG_REPEAT: u8 = 0b10000000;
+const TAG_REPEAT_COUNT_MASK: u8 = 0b01111111;
+/// Frame tag bits for the fields that changed since the previous frame
+const TAG_JS_X: u8 = 0b00000001;
+const TAG_JS_Y: u8 = 0b00000010;
+const TAG_BUTTONS: u8 = 0b00000100;
+const TAG_CHANGE_MASK: u8 = TAG_JS_X | TAG_JS_Y | TAG_BUTTONS;
+/// The number of bytes used by the packed button states
+const BUTTONS_SIZE: usize = 3;
+
+/// Identifies the replay format and the map that a replay was recorded on
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ReplayHeader {
+    /// The version of the replay format
+    pub version: u8,
+    /// The name field of the map
+    pub map_name: [u8; MAP_NAME_SIZE],
+    /// The author field of the map
+    pub map_author: [u8; MAP_AUTHOR_SIZE],
+    /// A checksum of the complete serialised map, so edited maps can be detected
+    pub map_checksum: u32,
+}
+
+impl ReplayHeader {
+    /// Creates a header for a replay of the specified map
+    ///
+    /// Arguments:
+    /// * `data`: The serialised map, as output by the puzzle! macro
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{ReplayHeader, REPLAY_VERSION};
+    /// let header = ReplayHeader::for_map(&[0; 199]);
+    /// assert_eq!(REPLAY_VERSION, header.version);
+    /// ```
+    pub fn for_map<const DATA: usize>(data: &[u8; DATA]) -> ReplayHeader {
+        let mut map_name = [0; MAP_NAME_SIZE];
+        let mut map_author = [0; MAP_AUTHOR_SIZE];
+        map_name.copy_from_slice(&data[MAP_NAME_OFFSET..MAP_NAME_OFFSET + MAP_NAME_SIZE]);
+        map_author.copy_from_slice(&data[MAP_AUTHOR_OFFSET..MAP_AUTHOR_OFFSET + MAP_AUTHOR_SIZE]);
+
+        ReplayHeader {
+            version: REPLAY_VERSION,
+            map_name,
+            map_author,
+            map_checksum: ReplayHeader::checksum(data),
+        }
+    }
+
+    /// Checks if this header was created for the specified map
+    ///
+    /// Arguments:
+    /// * `data`: The serialised map
+    ///
+    /// Return value:
+    /// True if the map identity and checksum match
+    pub fn matches_map<const DATA: usize>(&self, data: &[u8; DATA]) -> bool {
+        let expected = ReplayHeader::for_map(data);
+        self.map_name == expected.map_name
+            && self.map_author == expected.map_author
+            && self.map_checksum == expected.map_checksum
+    }
+
+    /// 32 bit FNV-1a hash of the serialised map
+    fn checksum(data: &[u8]) -> u32 {
+        data.iter()
+            .fold(0x811c9dc5u32, |hash, byte| (hash ^ *byte as u32).wrapping_mul(0x01000193))
+    }
+
+    /// Writes the header to the start of a buffer
+    ///
+    /// Arguments:
+    /// * `buffer`: The buffer to write to
+    ///
+    /// Return value:
+    /// The number of bytes written, or an error if the buffer is too small
+    fn write(&self, buffer: &mut [u8]) -> Result<usize, ReplayError> {
+        if buffer.len() < HEADER_SIZE {
+            return Err(ReplayError::BufferFull);
+        }
+
+        let name_offset = REPLAY_MAGIC.len() + 1;
+        let author_offset = name_offset + MAP_NAME_SIZE;
+        let checksum_offset = author_offset + MAP_AUTHOR_SIZE;
+        buffer[..REPLAY_MAGIC.len()].copy_from_slice(&REPLAY_MAGIC);
+        buffer[REPLAY_MAGIC.len()] = self.version;
+        buffer[name_offset..author_offset].copy_from_slice(&self.map_name);
+        buffer[author_offset..checksum_offset].copy_from_slice(&self.map_author);
+        buffer[checksum_offset..HEADER_SIZE].copy_from_slice(&self.map_checksum.to_le_bytes());
+        Ok(HEADER_SIZE)
+    }
+
+    /// Reads a header from the start of a replay
+    ///
+    /// Arguments:
+    /// * `data`: The replay to read
+    ///
+    /// Return value:
+    /// The header, or an error if it is missing or of an unsupported version
+    fn read(data: &[u8]) -> Result<ReplayHeader, ReplayError> {
+        if data.len() < REPLAY_MAGIC.len() || data[..REPLAY_MAGIC.len()] != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        if data.len() < HEADER_SIZE {
+            return Err(ReplayError::Truncated);
+        }
+
+        let version = data[REPLAY_MAGIC.len()];
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion);
+        }
+
+        let name_offset = REPLAY_MAGIC.len() + 1;
+        let author_offset = name_offset + MAP_NAME_SIZE;
+        let checksum_offset = author_offset + MAP_AUTHOR_SIZE;
+        let mut map_name = [0; MAP_NAME_SIZE];
+        let mut map_author = [0; MAP_AUTHOR_SIZE];
+        let mut map_checksum = [0; 4];
+        map_name.copy_from_slice(&data[name_offset..author_offset]);
+        map_author.copy_from_slice(&data[author_offset..checksum_offset]);
+        map_checksum.copy_from_slice(&data[checksum_offset..HEADER_SIZE]);
+
+        Ok(ReplayHeader {
+            version,
+            map_name,
+            map_author,
+            map_checksum: u32::from_le_bytes(map_checksum),
+        })
+    }
+}
+
+/// Records the input of each frame into a caller supplied buffer. Only the fields that change
+/// between frames are stored, and runs of unchanged frames are stored as a single byte
+pub struct ReplayRecorder<'a> {
+    /// The buffer being written to
+    buffer: &'a mut [u8],
+    /// The number of bytes of the buffer used
+    length: usize,
+    /// The input of the previous frame
+    previous: InputState,
+    /// The number of unchanged frames not yet written
+    repeats: u8,
+}
+
+impl<'a> ReplayRecorder<'a> {
+    /// Creates a recorder, writing the header to the buffer
+    ///
+    /// Arguments:
+    /// * `buffer`: The buffer to record into
+    /// * `header`: The header identifying the map being played
+    ///
+    /// Return value:
+    /// The recorder, or an error if the buffer cannot hold the header
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{ReplayHeader, ReplayRecorder};
+    /// let mut buffer = [0; 256];
+    /// let recorder = ReplayRecorder::new(&mut buffer, ReplayHeader::for_map(&[0; 199]));
+    /// assert!(recorder.is_ok());
+    /// ```
+    pub fn new(
+        buffer: &'a mut [u8],
+        header: ReplayHeader,
+    ) -> Result<ReplayRecorder<'a>, ReplayError> {
+        let length = header.write(buffer)?;
+        Ok(ReplayRecorder {
+            buffer,
+            length,
+            previous: InputState::new(),
+            repeats: 0,
+        })
+    }
+
+    /// Records the input of a single frame
+    ///
+    /// Arguments:
+    /// * `input`: The input passed to the state machine this frame
+    ///
+    /// Return value:
+    /// An error if the buffer is full. The frame is not recorded in this case
+    pub fn record(&mut self, input: &InputState) -> Result<(), ReplayError> {
+        if *input == self.previous {
+            // Reserve space for the repeat tag as each run starts, so that pending frames can
+            // always be written
+            let reserved_size = match self.repeats {
+                0 => 1,
+                TAG_REPEAT_COUNT_MASK => 2,
+                _ => 0,
+            };
+            if self.length + reserved_size > self.buffer.len() {
+                return Err(ReplayError::BufferFull);
+            }
+
+            if self.repeats == TAG_REPEAT_COUNT_MASK {
+                self.flush_repeats()?;
+            }
+            self.repeats += 1;
+            return Ok(());
+        }
+
+        let previous_buttons = pack_buttons(&self.previous);
+        let buttons = pack_buttons(input);
+        let mut tag = 0;
+        let mut size = 1;
+        if input.js_x != self.previous.js_x {
+            tag |= TAG_JS_X;
+            size += 2;
+        }
+        if input.js_y != self.previous.js_y {
+            tag |= TAG_JS_Y;
+            size += 2;
+        }
+        if buttons != previous_buttons {
+            tag |= TAG_BUTTONS;
+            size += BUTTONS_SIZE;
+        }
+
+        // Check for space up front so that a failed frame leaves the buffer unchanged
+        let repeat_size = if self.repeats > 0 { 1 } else { 0 };
+        if self.length + repeat_size + size > self.buffer.len() {
+            return Err(ReplayError::BufferFull);
+        }
+
+        self.flush_repeats()?;
+        self.push(&[tag]);
+        if tag & TAG_JS_X != 0 {
+            self.push(&input.js_x.to_le_bytes());
+        }
+        if tag & TAG_JS_Y != 0 {
+            self.push(&input.js_y.to_le_bytes());
+        }
+        if tag & TAG_BUTTONS != 0 {
+            self.push(&buttons.to_le_bytes()[..BUTTONS_SIZE]);
+        }
+        self.previous = *input;
+        Ok(())
+    }
+
+    /// Finishes recording, writing any pending unchanged frames
+    ///
+    /// Return value:
+    /// The length of the replay at the start of the buffer, or an error if the buffer is full
+    pub fn finish(mut self) -> Result<usize, ReplayError> {
+        self.flush_repeats()?;
+        Ok(self.length)
+    }
+
+    /// Writes any pending unchanged frames as a single repeat tag
+    fn flush_repeats(&mut self) -> Result<(), ReplayError> {
+        if self.repeats == 0 {
+            return Ok(());
+        }
+        if self.length == self.buffer.len() {
+            return Err(ReplayError::BufferFull);
+        }
+
+        self.push(&[TAG_REPEAT | self.repeats]);
+        self.repeats = 0;
+        Ok(())
+    }
+
+    /// Appends bytes to the buffer. Space must have been checked by the caller
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer[self.length..self.length + bytes.len()].copy_from_slice(bytes);
+        self.length += bytes.len();
+    }
+}
+
+/// Plays back a recorded replay, one frame of input at a time
+pub struct ReplayPlayer<'a> {
+    /// The header of the replay
+    header: ReplayHeader,
+    /// The complete replay
+    data: &'a [u8],
+    /// The offset of the next frame tag
+    position: usize,
+    /// The input of the most recent frame
+    input: InputState,
+    /// The number of frames left that repeat the most recent input
+    repeats: u8,
+}
+
+impl<'a> ReplayPlayer<'a> {
+    /// Creates a player, reading the header of the replay
+    ///
+    /// Arguments:
+    /// * `data`: The replay, as returned by ReplayRecorder::finish
+    ///
+    /// Return value:
+    /// The player, or an error if the header is missing or of an unsupported version
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{ReplayError, ReplayPlayer};
+    /// assert_eq!(Err(ReplayError::BadMagic), ReplayPlayer::new(&[0; 8]).map(|_| ()));
+    /// ```
+    pub fn new(data: &'a [u8]) -> Result<ReplayPlayer<'a>, ReplayError> {
+        let header = ReplayHeader::read(data)?;
+        Ok(ReplayPlayer {
+            header,
+            data,
+            position: HEADER_SIZE,
+            input: InputState::new(),
+            repeats: 0,
+        })
+    }
+
+    /// Gets the header of the replay
+    pub fn get_header(&self) -> ReplayHeader {
+        self.header
+    }
+
+    /// Reads the input of the next frame
+    ///
+    /// Return value:
+    /// The input, None at the end of the replay, or an error if the replay is malformed
+    pub fn next_input(&mut self) -> Result<Option<InputState>, ReplayError> {
+        if self.repeats > 0 {
+            self.repeats -= 1;
+            return Ok(Some(self.input));
+        }
+        if self.position == self.data.len() {
+            return Ok(None);
+        }
+
+        let tag = self.pull(1)?[0];
+        if tag & TAG_REPEAT != 0 {
+            let count = tag & TAG_REPEAT_COUNT_MASK;
+            if count == 0 {
+                return Err(ReplayError::Corrupt);
+            }
+            self.repeats = count - 1;
+            return Ok(Some(self.input));
+        }
+        if tag & !TAG_CHANGE_MASK != 0 || tag == 0 {
+            return Err(ReplayError::Corrupt);
+        }
+
+        let mut input = self.input;
+        if tag & TAG_JS_X != 0 {
+            let bytes = self.pull(2)?;
+            input.js_x = i16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        if tag & TAG_JS_Y != 0 {
+            let bytes = self.pull(2)?;
+            input.js_y = i16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        if tag & TAG_BUTTONS != 0 {
+            let bytes = self.pull(BUTTONS_SIZE)?;
+            unpack_buttons(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]), &mut input);
+        }
+
+        self.input = input;
+        Ok(Some(input))
+    }
+
+    /// Feeds every remaining frame of the replay into a state machine
+    ///
+    /// Arguments:
+    /// * `state_machine`: The state machine to tick
+    ///
+    /// Return value:
+    /// The number of frames played, or an error if the replay is malformed
+    pub fn play(&mut self, state_machine: &mut StateMachine) -> Result<u32, ReplayError> {
+        let mut frames = 0;
+        while let Some(input) = self.next_input()? {
+            state_machine.tick(&input);
+            frames += 1;
+        }
+
+        Ok(frames)
+    }
+
+    /// Reads bytes from the replay
+    fn pull(&mut self, count: usize) -> Result<&'a [u8], ReplayError> {
+        if self.position + count > self.data.len() {
+            return Err(ReplayError::Truncated);
+        }
+
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+}
+
+/// Packs the three flags of a button into the lowest three bits
+fn pack_button(button: &ButtonState) -> u32 {
+    button.down as u32 | (button.pressed as u32) << 1 | (button.released as u32) << 2
+}
+
+/// Unpacks the three flags of a button from the lowest three bits
+fn unpack_button(bits: u32) -> ButtonState {
+    ButtonState {
+        down: bits & 0b001 != 0,
+        pressed: bits & 0b010 != 0,
+        released: bits & 0b100 != 0,
+    }
+}
+
+/// Packs every button into 24 bits, three bits per button
+fn pack_buttons(input: &InputState) -> u32 {
+    [
+        &input.js_up,
+        &input.js_down,
+        &input.js_left,
+        &input.js_right,
+        &input.btn_a,
+        &input.btn_b,
+        &input.btn_start,
+        &input.btn_select,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |bits, (index, button)| bits | pack_button(button) << (index * 3))
+}
+
+/// Unpacks every button from the output of pack_buttons
+fn unpack_buttons(bits: u32, input: &mut InputState) {
+    let buttons = [
+        &mut input.js_up,
+        &mut input.js_down,
+        &mut input.js_left,
+        &mut input.js_right,
+        &mut input.btn_a,
+        &mut input.btn_b,
+        &mut input.btn_start,
+        &mut input.btn_select,
+    ];
+    for (index, button) in buttons.into_iter().enumerate() {
+        *button = unpack_button(bits >> (index * 3));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::World;
+
+    /// Builds a varied sequence of inputs to record
+    fn input_sequence() -> [InputState; 6] {
+        let idle = InputState::new();
+        let mut pressed = idle;
+        pressed.btn_a = ButtonState::down();
+        let mut held = pressed;
+        held.btn_a.pressed = false;
+        held.js_x = -2048;
+        let mut released = held;
+        released.btn_a = ButtonState::up();
+        released.js_y = 2047;
+
+        [idle, pressed, held, held, released, idle]
+    }
+
+    /// GIVEN a sequence of inputs
+    /// WHEN it is recorded and played back
+    /// THEN the same inputs are returned in order
+    #[test]
+    fn round_trip() {
+        let mut buffer = [0; 256];
+        let header = ReplayHeader::for_map(&[0; 199]);
+        let mut recorder = ReplayRecorder::new(&mut buffer, header).unwrap();
+        for input in input_sequence().iter() {
+            recorder.record(input).unwrap();
+        }
+        let length = recorder.finish().unwrap();
+
+        let mut player = ReplayPlayer::new(&buffer[..length]).unwrap();
+        assert_eq!(header, player.get_header());
+        for input in input_sequence().iter() {
+            assert_eq!(Ok(Some(*input)), player.next_input());
+        }
+        assert_eq!(Ok(None), player.next_input());
+    }
+
+    /// GIVEN a long run of frames without input
+    /// WHEN they are recorded
+    /// THEN they are stored as a few repeat tags
+    #[test]
+    fn idle_frames_compress() {
+        let mut buffer = [0; 256];
+        let mut recorder =
+            ReplayRecorder::new(&mut buffer, ReplayHeader::for_map(&[0; 199])).unwrap();
+        for _ in 0..300 {
+            recorder.record(&InputState::new()).unwrap();
+        }
+        let length = recorder.finish().unwrap();
+
+        assert_eq!(HEADER_SIZE + 3, length);
+        let mut player = ReplayPlayer::new(&buffer[..length]).unwrap();
+        let mut state_machine = StateMachine::new(World::new());
+        assert_eq!(Ok(300), player.play(&mut state_machine));
+    }
+
+    /// GIVEN a buffer with no room after the header
+    /// WHEN a changed frame is recorded
+    /// THEN the buffer is reported as full
+    #[test]
+    fn buffer_full() {
+        let mut buffer = [0; HEADER_SIZE];
+        let mut recorder =
+            ReplayRecorder::new(&mut buffer, ReplayHeader::for_map(&[0; 199])).unwrap();
+
+        assert_eq!(Err(ReplayError::BufferFull), recorder.record(&input_sequence()[1]));
+    }
+
+    /// GIVEN a buffer with room for two repeat tags after the header
+    /// WHEN more unchanged frames are recorded than two tags can hold
+    /// THEN the frame that does not fit is reported as full
+    /// AND every frame before it is kept
+    #[test]
+    fn buffer_full_of_repeats() {
+        let mut buffer = [0; HEADER_SIZE + 2];
+        let mut recorder =
+            ReplayRecorder::new(&mut buffer, ReplayHeader::for_map(&[0; 199])).unwrap();
+        let max_frames = TAG_REPEAT_COUNT_MASK as u32 * 2;
+        for _ in 0..max_frames {
+            recorder.record(&InputState::new()).unwrap();
+        }
+
+        assert_eq!(Err(ReplayError::BufferFull), recorder.record(&InputState::new()));
+        let length = recorder.finish().unwrap();
+        assert_eq!(HEADER_SIZE + 2, length);
+        let mut player = ReplayPlayer::new(&buffer[..length]).unwrap();
+        let mut state_machine = StateMachine::new(World::new());
+        assert_eq!(Ok(max_frames), player.play(&mut state_machine));
+    }
+
+    /// GIVEN replays with a bad version or a truncated frame
+    /// WHEN they are played
+    /// THEN the problem is reported
+    #[test]
+    fn malformed_replays() {
+        let mut buffer = [0; 256];
+        let mut recorder =
+            ReplayRecorder::new(&mut buffer, ReplayHeader::for_map(&[0; 199])).unwrap();
+        recorder.record(&input_sequence()[2]).unwrap();
+        let length = recorder.finish().unwrap();
+
+        let mut player = ReplayPlayer::new(&buffer[..length - 1]).unwrap();
+        assert_eq!(Err(ReplayError::Truncated), player.next_input());
+
+        buffer[REPLAY_MAGIC.len()] = REPLAY_VERSION + 1;
+        assert_eq!(
+            Err(ReplayError::UnsupportedVersion),
+            ReplayPlayer::new(&buffer[..length]).map(|_| ())
+        );
+    }
+
+    /// GIVEN a header created for a map
+    /// WHEN it is compared against that map and an edited copy
+    /// THEN only the original map matches
+    #[test]
+    fn header_identifies_map() {
+        let mut map = [0; 199];
+        map[0] = b'A';
+        let header = ReplayHeader::for_map(&map);
+        let mut edited = map;
+        edited[198] = 1;
+
+        assert_eq!(b'A', header.map_name[0]);
+        assert!(header.matches_map(&map));
+        assert!(!header.matches_map(&edited));
+    }
+}
@@ -0,0 +1,14 @@
+/// Reasons that recording or playing back a replay can fail
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReplayError {
+    /// The recording buffer has no room for another frame
+    BufferFull,
+    /// The data does not start with the replay magic number
+    BadMagic,
+    /// The replay was written by an unsupported version of the format
+    UnsupportedVersion,
+    /// The replay ended part way through a header or frame
+    Truncated,
+    /// The replay contains a frame that cannot be decoded
+    Corrupt,
+}
@@ -0,0 +1,31 @@
+/// Determines what happens to a rocket when a cat enters it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RocketPenalty {
+    /// The rocket is destroyed, failing the puzzle. Used in puzzle mode
+    Destroy,
+    /// The rocket survives but the hit is reported, so that the owner can be penalised.
+    /// Used in battle mode
+    Hit,
+}
+
+/// The ways a walker entering a rocket can affect it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RocketEventKind {
+    /// A mouse boarded the rocket
+    Rescue,
+    /// A cat entered the rocket, which survived
+    Hit,
+    /// A cat entered the rocket and destroyed it
+    Destroyed,
+}
+
+/// Records a walker entering a rocket during a tick, so that it can be animated
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct RocketEvent {
+    /// The x coordinate of the rocket
+    pub x: usize,
+    /// The y coordinate of the rocket
+    pub y: usize,
+    /// What happened to the rocket
+    pub kind: RocketEventKind,
+}
@@ -0,0 +1,144 @@
+use crate::{Direction, FixedPoint, RocketPenalty, Walker, WalkerType};
+
+/// A way for a walker to turn away from a wall
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Turn {
+    /// Turn 90 degrees clockwise
+    Right,
+    /// Turn 90 degrees anticlockwise
+    Left,
+    /// Turn 180 degrees
+    Back,
+}
+
+impl Turn {
+    /// Gets the direction faced after turning
+    ///
+    /// Arguments:
+    /// * `direction`: The direction faced before turning
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Direction, Turn};
+    /// assert_eq!(Direction::Right, Turn::Right.apply(Direction::Up));
+    /// ```
+    pub fn apply(self, direction: Direction) -> Direction {
+        match self {
+            Turn::Right => direction.turn_right(),
+            Turn::Left => direction.turn_left(),
+            Turn::Back => direction.turn_around(),
+        }
+    }
+}
+
+/// Determines which arrows are diminished by walkers
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DiminishPolicy {
+    /// Arrows are never diminished
+    Never,
+    /// Arrows are diminished by cats they turn around. Used in puzzle and battle mode
+    CatsHeadOn,
+    /// Arrows are diminished by every cat they turn
+    CatsTurned,
+}
+
+/// Determines when a world is won
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WinCondition {
+    /// Won once there are mice and all of them have been rescued. Used in puzzle mode
+    AllMiceRescued,
+    /// Won once the given number of mice have been rescued since the world was reset
+    MiceRescued(usize),
+    /// Never won. Used in battle mode, where the battle decides the winner
+    Never,
+}
+
+/// Determines when a world is lost to mice dying. Destroyed rockets are covered by the rocket
+/// penalty
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LossCondition {
+    /// Lost as soon as any mouse dies. Used in puzzle mode
+    AnyMouseDies,
+    /// Lost once the given number of mice have died since the world was reset
+    MiceDie(usize),
+    /// Never lost to mice dying. Used in battle mode
+    Never,
+}
+
+/// The rules a world is simulated with, so that puzzles, battles and custom variants can run on
+/// the same engine
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Ruleset {
+    /// The distance mice move each step. Must be more than zero and less than a square
+    pub mouse_speed: FixedPoint,
+    /// The distance cats move each step. Must be more than zero and less than a square
+    pub cat_speed: FixedPoint,
+    /// The turns tried, in order, by a walker blocked by a wall. A walker blocked in every
+    /// direction tried keeps going straight
+    pub turn_priority: [Turn; 3],
+    /// Which arrows are diminished by walkers
+    pub diminish_policy: DiminishPolicy,
+    /// What happens to rockets entered by cats
+    pub rocket_penalty: RocketPenalty,
+    /// When the world is won
+    pub win_condition: WinCondition,
+    /// When the world is lost to mice dying
+    pub loss_condition: LossCondition,
+}
+
+impl Ruleset {
+    /// Creates the rules of puzzle mode, where every mouse must be rescued without losing any
+    /// mice or rockets
+    /// #examples
+    /// ```
+    /// use simulation::{Ruleset, RocketPenalty};
+    /// let ruleset = Ruleset::puzzle();
+    /// assert_eq!(RocketPenalty::Destroy, ruleset.rocket_penalty);
+    /// ```
+    pub fn puzzle() -> Ruleset {
+        Ruleset {
+            mouse_speed: Walker::default_speed(WalkerType::Mouse),
+            cat_speed: Walker::default_speed(WalkerType::Cat),
+            turn_priority: [Turn::Right, Turn::Left, Turn::Back],
+            diminish_policy: DiminishPolicy::CatsHeadOn,
+            rocket_penalty: RocketPenalty::Destroy,
+            win_condition: WinCondition::AllMiceRescued,
+            loss_condition: LossCondition::AnyMouseDies,
+        }
+    }
+
+    /// Creates the rules of battle mode, where rockets survive cats and the world is never won
+    /// or lost, as the battle decides the winner
+    /// #examples
+    /// ```
+    /// use simulation::{Ruleset, WinCondition};
+    /// let ruleset = Ruleset::battle();
+    /// assert_eq!(WinCondition::Never, ruleset.win_condition);
+    /// ```
+    pub fn battle() -> Ruleset {
+        Ruleset {
+            rocket_penalty: RocketPenalty::Hit,
+            win_condition: WinCondition::Never,
+            loss_condition: LossCondition::Never,
+            ..Ruleset::puzzle()
+        }
+    }
+
+    /// Gets the distance a type of walker moves each step
+    ///
+    /// Arguments:
+    /// * `walker_type`: The type of walker
+    pub fn get_speed(&self, walker_type: WalkerType) -> FixedPoint {
+        match walker_type {
+            WalkerType::Mouse => self.mouse_speed,
+            WalkerType::Cat => self.cat_speed,
+        }
+    }
+}
+
+/// The puzzle rules are the default
+impl Default for Ruleset {
+    fn default() -> Ruleset {
+        Ruleset::puzzle()
+    }
+}
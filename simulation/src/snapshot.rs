@@ -0,0 +1,423 @@
+use crate::{
+    DiminishPolicy, Direction, FixedPoint, LossCondition, Player, RocketEventKind, RocketPenalty,
+    Ruleset, SnapshotError, Spawner, TileType, Turn, Walker, WalkerId, WalkerState, WalkerType,
+    WinCondition, WorldState,
+};
+
+/// The bytes every snapshot starts with
+pub(crate) const SNAPSHOT_MAGIC: [u8; 4] = *b"SRSS";
+/// The version of the snapshot format written by this build. Only this version can be
+/// restored, so it must be increased whenever a released format changes
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// Writes the fields of a snapshot into a caller supplied buffer
+pub(crate) struct SnapshotWriter<'a> {
+    /// The buffer being written to
+    buffer: &'a mut [u8],
+    /// The number of bytes of the buffer used
+    length: usize,
+}
+
+impl<'a> SnapshotWriter<'a> {
+    /// Creates a writer, writing the magic number and version to the buffer
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Result<SnapshotWriter<'a>, SnapshotError> {
+        let mut writer = SnapshotWriter { buffer, length: 0 };
+        writer.write_bytes(&SNAPSHOT_MAGIC)?;
+        writer.write_u8(SNAPSHOT_VERSION)?;
+        Ok(writer)
+    }
+
+    /// Gets the number of bytes written
+    pub(crate) fn finish(self) -> usize {
+        self.length
+    }
+
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SnapshotError> {
+        if self.length + bytes.len() > self.buffer.len() {
+            return Err(SnapshotError::BufferFull);
+        }
+
+        self.buffer[self.length..self.length + bytes.len()].copy_from_slice(bytes);
+        self.length += bytes.len();
+        Ok(())
+    }
+
+    pub(crate) fn write_u8(&mut self, value: u8) -> Result<(), SnapshotError> {
+        self.write_bytes(&[value])
+    }
+
+    pub(crate) fn write_u16(&mut self, value: u16) -> Result<(), SnapshotError> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) -> Result<(), SnapshotError> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    pub(crate) fn write_u64(&mut self, value: u64) -> Result<(), SnapshotError> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    pub(crate) fn write_tile(&mut self, tile_type: TileType) -> Result<(), SnapshotError> {
+        self.write_u8(match tile_type {
+            TileType::Empty => 0,
+            TileType::Rocket => 1,
+            TileType::RocketDestroyed => 2,
+            TileType::Hole => 3,
+            TileType::Up => 4,
+            TileType::UpHalf => 5,
+            TileType::Down => 6,
+            TileType::DownHalf => 7,
+            TileType::Left => 8,
+            TileType::LeftHalf => 9,
+            TileType::Right => 10,
+            TileType::RightHalf => 11,
+        })
+    }
+
+    pub(crate) fn write_direction(&mut self, direction: Direction) -> Result<(), SnapshotError> {
+        self.write_u8(match direction {
+            Direction::Up => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Right => 3,
+        })
+    }
+
+    pub(crate) fn write_world_state(&mut self, state: WorldState) -> Result<(), SnapshotError> {
+        self.write_u8(match state {
+            WorldState::Stopped => 0,
+            WorldState::Running => 1,
+            WorldState::RunningFast => 2,
+            WorldState::Success => 3,
+            WorldState::Defeat => 4,
+        })
+    }
+
+    pub(crate) fn write_rocket_penalty(
+        &mut self,
+        rocket_penalty: RocketPenalty,
+    ) -> Result<(), SnapshotError> {
+        self.write_u8(match rocket_penalty {
+            RocketPenalty::Destroy => 0,
+            RocketPenalty::Hit => 1,
+        })
+    }
+
+    pub(crate) fn write_ruleset(&mut self, ruleset: &Ruleset) -> Result<(), SnapshotError> {
+        self.write_fixed_point(ruleset.mouse_speed)?;
+        self.write_fixed_point(ruleset.cat_speed)?;
+        for turn in ruleset.turn_priority {
+            self.write_u8(match turn {
+                Turn::Right => 0,
+                Turn::Left => 1,
+                Turn::Back => 2,
+            })?;
+        }
+        self.write_u8(match ruleset.diminish_policy {
+            DiminishPolicy::Never => 0,
+            DiminishPolicy::CatsHeadOn => 1,
+            DiminishPolicy::CatsTurned => 2,
+        })?;
+        self.write_rocket_penalty(ruleset.rocket_penalty)?;
+        let (win_kind, win_count) = match ruleset.win_condition {
+            WinCondition::AllMiceRescued => (0, 0),
+            WinCondition::MiceRescued(count) => (1, count),
+            WinCondition::Never => (2, 0),
+        };
+        self.write_u8(win_kind)?;
+        self.write_u32(win_count as u32)?;
+        let (loss_kind, loss_count) = match ruleset.loss_condition {
+            LossCondition::AnyMouseDies => (0, 0),
+            LossCondition::MiceDie(count) => (1, count),
+            LossCondition::Never => (2, 0),
+        };
+        self.write_u8(loss_kind)?;
+        self.write_u32(loss_count as u32)
+    }
+
+    pub(crate) fn write_rocket_event_kind(
+        &mut self,
+        kind: RocketEventKind,
+    ) -> Result<(), SnapshotError> {
+        self.write_u8(match kind {
+            RocketEventKind::Rescue => 0,
+            RocketEventKind::Hit => 1,
+            RocketEventKind::Destroyed => 2,
+        })
+    }
+
+    pub(crate) fn write_player(&mut self, player: Player) -> Result<(), SnapshotError> {
+        self.write_u8(player.index() as u8)
+    }
+
+    pub(crate) fn write_walker_type(
+        &mut self,
+        walker_type: WalkerType,
+    ) -> Result<(), SnapshotError> {
+        self.write_u8(match walker_type {
+            WalkerType::Mouse => 0,
+            WalkerType::Cat => 1,
+        })
+    }
+
+    pub(crate) fn write_fixed_point(&mut self, value: FixedPoint) -> Result<(), SnapshotError> {
+        self.write_bytes(&value.integer_part().to_le_bytes())?;
+        self.write_bytes(&value.fractional_part().to_le_bytes())
+    }
+
+    /// Writes a walker's ID, exact position, direction, type and state
+    pub(crate) fn write_walker(&mut self, walker: &Walker) -> Result<(), SnapshotError> {
+        self.write_u32(walker.get_id().get_value())?;
+        self.write_fixed_point(walker.get_x())?;
+        self.write_fixed_point(walker.get_y())?;
+        self.write_direction(walker.get_direction())?;
+        self.write_walker_type(walker.get_type())?;
+        self.write_u8(match walker.get_state() {
+            WalkerState::Alive => 0,
+            WalkerState::Dead => 1,
+            WalkerState::Rescued => 2,
+        })
+    }
+
+    /// Writes a spawner's position, direction, type and progress through its countdown
+    pub(crate) fn write_spawner(&mut self, spawner: &Spawner) -> Result<(), SnapshotError> {
+        self.write_u8(spawner.get_x() as u8)?;
+        self.write_u8(spawner.get_y() as u8)?;
+        self.write_direction(spawner.get_direction())?;
+        self.write_walker_type(spawner.get_type())?;
+        self.write_u32(spawner.get_interval())?;
+        self.write_u32(spawner.get_countdown())
+    }
+}
+
+/// Reads the fields of a snapshot written by SnapshotWriter
+pub(crate) struct SnapshotReader<'a> {
+    /// The snapshot
+    data: &'a [u8],
+    /// The offset of the next field
+    position: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    /// Creates a reader, checking the magic number and version
+    pub(crate) fn new(data: &'a [u8]) -> Result<SnapshotReader<'a>, SnapshotError> {
+        let mut reader = SnapshotReader { data, position: 0 };
+        let magic = reader
+            .read_bytes(SNAPSHOT_MAGIC.len())
+            .map_err(|_| SnapshotError::BadMagic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        if reader.read_u8()? != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion);
+        }
+
+        Ok(reader)
+    }
+
+    pub(crate) fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.position + count > self.data.len() {
+            return Err(SnapshotError::Truncated);
+        }
+
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_u16(&mut self) -> Result<u16, SnapshotError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, SnapshotError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, SnapshotError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn read_tile(&mut self) -> Result<TileType, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => TileType::Empty,
+            1 => TileType::Rocket,
+            2 => TileType::RocketDestroyed,
+            3 => TileType::Hole,
+            4 => TileType::Up,
+            5 => TileType::UpHalf,
+            6 => TileType::Down,
+            7 => TileType::DownHalf,
+            8 => TileType::Left,
+            9 => TileType::LeftHalf,
+            10 => TileType::Right,
+            11 => TileType::RightHalf,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_direction(&mut self) -> Result<Direction, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            3 => Direction::Right,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_world_state(&mut self) -> Result<WorldState, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => WorldState::Stopped,
+            1 => WorldState::Running,
+            2 => WorldState::RunningFast,
+            3 => WorldState::Success,
+            4 => WorldState::Defeat,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_rocket_penalty(&mut self) -> Result<RocketPenalty, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => RocketPenalty::Destroy,
+            1 => RocketPenalty::Hit,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_ruleset(&mut self) -> Result<Ruleset, SnapshotError> {
+        let mouse_speed = self.read_fixed_point()?;
+        let cat_speed = self.read_fixed_point()?;
+        for speed in [mouse_speed, cat_speed] {
+            if speed.scaled() <= 0 || speed.scaled() >= <FixedPoint>::FRACTIONAL_UNITS as i32 {
+                return Err(SnapshotError::Corrupt);
+            }
+        }
+        let mut turn_priority = [Turn::Right; 3];
+        for turn in turn_priority.iter_mut() {
+            *turn = match self.read_u8()? {
+                0 => Turn::Right,
+                1 => Turn::Left,
+                2 => Turn::Back,
+                _ => return Err(SnapshotError::Corrupt),
+            };
+        }
+        let diminish_policy = match self.read_u8()? {
+            0 => DiminishPolicy::Never,
+            1 => DiminishPolicy::CatsHeadOn,
+            2 => DiminishPolicy::CatsTurned,
+            _ => return Err(SnapshotError::Corrupt),
+        };
+        let rocket_penalty = self.read_rocket_penalty()?;
+        let win_kind = self.read_u8()?;
+        let win_count = self.read_u32()? as usize;
+        let win_condition = match win_kind {
+            0 => WinCondition::AllMiceRescued,
+            1 => WinCondition::MiceRescued(win_count),
+            2 => WinCondition::Never,
+            _ => return Err(SnapshotError::Corrupt),
+        };
+        let loss_kind = self.read_u8()?;
+        let loss_count = self.read_u32()? as usize;
+        let loss_condition = match loss_kind {
+            0 => LossCondition::AnyMouseDies,
+            1 => LossCondition::MiceDie(loss_count),
+            2 => LossCondition::Never,
+            _ => return Err(SnapshotError::Corrupt),
+        };
+
+        Ok(Ruleset {
+            mouse_speed,
+            cat_speed,
+            turn_priority,
+            diminish_policy,
+            rocket_penalty,
+            win_condition,
+            loss_condition,
+        })
+    }
+
+    pub(crate) fn read_rocket_event_kind(&mut self) -> Result<RocketEventKind, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => RocketEventKind::Rescue,
+            1 => RocketEventKind::Hit,
+            2 => RocketEventKind::Destroyed,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_player(&mut self) -> Result<Player, SnapshotError> {
+        Player::ALL
+            .get(self.read_u8()? as usize)
+            .copied()
+            .ok_or(SnapshotError::Corrupt)
+    }
+
+    pub(crate) fn read_walker_type(&mut self) -> Result<WalkerType, SnapshotError> {
+        Ok(match self.read_u8()? {
+            0 => WalkerType::Mouse,
+            1 => WalkerType::Cat,
+            _ => return Err(SnapshotError::Corrupt),
+        })
+    }
+
+    pub(crate) fn read_fixed_point(&mut self) -> Result<FixedPoint, SnapshotError> {
+        let value = self.read_u8()? as i8;
+        let fractional = self.read_bytes(2)?;
+        let fractional = i16::from_le_bytes([fractional[0], fractional[1]]);
+        let units = <FixedPoint>::FRACTIONAL_UNITS;
+        if fractional <= -units || fractional >= units {
+            return Err(SnapshotError::Corrupt);
+        }
+        Ok(FixedPoint::new(value, fractional))
+    }
+
+    /// Reads a walker written by SnapshotWriter::write_walker
+    pub(crate) fn read_walker(&mut self) -> Result<Walker, SnapshotError> {
+        let id = WalkerId::new(self.read_u32()?);
+        let x = self.read_fixed_point()?;
+        let y = self.read_fixed_point()?;
+        let direction = self.read_direction()?;
+        let walker_type = self.read_walker_type()?;
+        let walker_state = match self.read_u8()? {
+            0 => WalkerState::Alive,
+            1 => WalkerState::Dead,
+            2 => WalkerState::Rescued,
+            _ => return Err(SnapshotError::Corrupt),
+        };
+
+        Ok(Walker::restore(id, x, y, direction, walker_type, walker_state))
+    }
+
+    /// Reads a spawner written by SnapshotWriter::write_spawner
+    pub(crate) fn read_spawner(&mut self) -> Result<Spawner, SnapshotError> {
+        let x = self.read_u8()? as usize;
+        let y = self.read_u8()? as usize;
+        let direction = self.read_direction()?;
+        let walker_type = self.read_walker_type()?;
+        let interval = self.read_u32()?;
+        let countdown = self.read_u32()?;
+        if interval == 0 || countdown == 0 || countdown > interval {
+            return Err(SnapshotError::Corrupt);
+        }
+
+        Ok(Spawner::restore(x, y, direction, walker_type, interval, countdown))
+    }
+
+    /// Checks that the whole snapshot has been read
+    pub(crate) fn finish(self) -> Result<(), SnapshotError> {
+        if self.position != self.data.len() {
+            return Err(SnapshotError::Corrupt);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+/// Reasons that capturing or restoring a snapshot can fail
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SnapshotError {
+    /// The buffer is too small to hold the snapshot
+    BufferFull,
+    /// The data does not start with the snapshot magic number
+    BadMagic,
+    /// The snapshot was written by an unsupported version of the format
+    UnsupportedVersion,
+    /// The snapshot was taken of a world with different dimensions
+    WrongSize,
+    /// The snapshot ended early
+    Truncated,
+    /// The snapshot contains a value that cannot be decoded
+    Corrupt,
+}
@@ -0,0 +1,437 @@
+use crate::{Direction, TileType, World, WorldStateChange};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+/// An arrow placed by the solver
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PlacedArrow {
+    /// The x coordinate of the arrow
+    pub x: usize,
+    /// The y coordinate of the arrow
+    pub y: usize,
+    /// The direction of the arrow
+    pub direction: Direction,
+}
+
+/// A set of arrow placements that wins the puzzle
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Solution {
+    /// The arrows to place, ordered by row then column
+    pub arrows: Vec<PlacedArrow>,
+    /// The number of ticks taken to win
+    pub ticks: u32,
+}
+
+/// Limits on how much work the solver may do
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SolverBudget {
+    /// The maximum number of ticks to simulate each candidate placement for
+    pub max_ticks: u32,
+    /// The maximum number of candidate placements to simulate
+    pub max_nodes: usize,
+}
+
+/// The results of a search
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SolverReport {
+    /// The solutions found, fewest arrows first
+    pub solutions: Vec<Solution>,
+    /// The number of candidate placements simulated
+    pub nodes: usize,
+    /// True if every candidate placement was searched. False if the node budget ran out, or the
+    /// search stopped at the first solution
+    pub complete: bool,
+}
+
+/// The result of simulating a single candidate placement
+struct Run {
+    /// The number of ticks taken to win, if the puzzle was won
+    win_ticks: Option<u32>,
+    /// The squares visited by any walker during the run, ordered so that the search is repeatable
+    visited: BTreeSet<(usize, usize)>,
+}
+
+/// Searches for every minimal set of arrow placements that wins the puzzle, using the arrows in
+/// the world's stock. Placement sets are searched breadth first, so solutions using fewer arrows
+/// are found first. Supersets of a solution are not reported
+///
+/// Arguments:
+/// * `world`: The puzzle to solve. Any arrows already placed are kept
+/// * `budget`: The tick and node limits of the search
+///
+/// Return value:
+/// The solutions found, and whether the search was exhaustive
+///
+/// #examples
+/// ```
+/// use simulation::{find_all_solutions, SolverBudget, World};
+/// let report = find_all_solutions(&World::new(), SolverBudget { max_ticks: 100, max_nodes: 10 });
+/// assert!(report.solutions.is_empty());
+/// assert!(report.complete);
+/// ```
+pub fn find_all_solutions<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const TILES: usize,
+    const DATA: usize,
+>(
+    world: &World<WIDTH, HEIGHT, TILES, DATA>,
+    budget: SolverBudget,
+) -> SolverReport {
+    search(world, budget, false)
+}
+
+/// Searches for a set of arrow placements that wins the puzzle, stopping at the first one found.
+/// This will use as few arrows as possible
+///
+/// Arguments:
+/// * `world`: The puzzle to solve. Any arrows already placed are kept
+/// * `budget`: The tick and node limits of the search
+///
+/// Return value:
+/// At most one solution. The report is only complete if no solution exists
+///
+/// #examples
+/// ```
+/// use simulation::{find_first_solution, SolverBudget, World};
+/// let report = find_first_solution(&World::new(), SolverBudget { max_ticks: 100, max_nodes: 10 });
+/// assert!(report.solutions.is_empty());
+/// ```
+pub fn find_first_solution<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const TILES: usize,
+    const DATA: usize,
+>(
+    world: &World<WIDTH, HEIGHT, TILES, DATA>,
+    budget: SolverBudget,
+) -> SolverReport {
+    search(world, budget, true)
+}
+
+/// Breadth first search over sets of placements. Each set is only simulated once regardless of
+/// the order its arrows were placed in, and arrows are only added on squares that a walker
+/// visited, as an arrow anywhere else cannot change the outcome
+fn search<const WIDTH: usize, const HEIGHT: usize, const TILES: usize, const DATA: usize>(
+    world: &World<WIDTH, HEIGHT, TILES, DATA>,
+    budget: SolverBudget,
+    first_only: bool,
+) -> SolverReport {
+    let mut base = world.clone();
+    base.stop();
+
+    let mut report = SolverReport {
+        solutions: Vec::new(),
+        nodes: 0,
+        complete: true,
+    };
+    let mut seen: HashSet<Vec<PlacedArrow>> = HashSet::new();
+    let mut queue: VecDeque<Vec<PlacedArrow>> = VecDeque::new();
+    seen.insert(Vec::new());
+    queue.push_back(Vec::new());
+
+    while let Some(arrows) = queue.pop_front() {
+        // A solution with fewer arrows may have been found since this set was queued
+        if is_superset_of_solution(&report.solutions, &arrows) {
+            continue;
+        }
+        if report.nodes == budget.max_nodes {
+            report.complete = false;
+            break;
+        }
+        report.nodes += 1;
+
+        let mut candidate = base.clone();
+        let run = simulate(&mut candidate, &arrows, budget.max_ticks);
+        if let Some(ticks) = run.win_ticks {
+            report.solutions.push(Solution { arrows, ticks });
+            if first_only {
+                report.complete = false;
+                break;
+            }
+            continue;
+        }
+
+        // The stock left after placing this set, and the squares still free for an arrow
+        let stock = candidate.get_arrow_stock();
+        for &(x, y) in run.visited.iter() {
+            if candidate.get_arrow(x, y) != TileType::Empty {
+                continue;
+            }
+
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                if stock[direction] == 0 {
+                    continue;
+                }
+
+                let mut child = arrows.clone();
+                child.push(PlacedArrow { x, y, direction });
+                child.sort_by_key(|arrow| (arrow.y, arrow.x));
+
+                // A superset of a solution can only be a redundant solution
+                if !is_superset_of_solution(&report.solutions, &child) && seen.insert(child.clone())
+                {
+                    queue.push_back(child);
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// Checks whether a set of placements contains every arrow of a solution, so could only be a
+/// redundant solution
+///
+/// Arguments:
+/// * `solutions`: The solutions found so far
+/// * `arrows`: The set of placements to check
+///
+/// Return value:
+/// True if the set contains any of the solutions
+fn is_superset_of_solution(solutions: &[Solution], arrows: &[PlacedArrow]) -> bool {
+    solutions
+        .iter()
+        .any(|solution| solution.arrows.iter().all(|arrow| arrows.contains(arrow)))
+}
+
+/// Places a set of arrows and runs the world until it is won, lost or stalemated, or the tick
+/// budget runs out
+///
+/// Arguments:
+/// * `world`: A stopped world. This is left stopped with the arrows placed
+/// * `arrows`: The arrows to place
+/// * `max_ticks`: The maximum number of ticks to run
+///
+/// Return value:
+/// The ticks taken to win, if the puzzle was won, and every square visited by a walker
+fn simulate<const WIDTH: usize, const HEIGHT: usize, const TILES: usize, const DATA: usize>(
+    world: &mut World<WIDTH, HEIGHT, TILES, DATA>,
+    arrows: &[PlacedArrow],
+    max_ticks: u32,
+) -> Run {
+    let mut run = Run {
+        win_ticks: None,
+        visited: BTreeSet::new(),
+    };
+
+    for arrow in arrows {
+        if world
+            .place_arrow(arrow.x, arrow.y, arrow.direction)
+            .is_err()
+        {
+            return run;
+        }
+    }
+
+    world.start();
+    run.visited.extend(world.walker_squares());
+    for tick in 1..=max_ticks {
+        let change = world.tick();
+        run.visited.extend(world.walker_squares());
+        match change {
+            WorldStateChange::Win => {
+                run.win_ticks = Some(tick);
+                break;
+            }
+            // Looping placements can never win, so stop simulating them early
+            WorldStateChange::Lose
+            | WorldStateChange::RocketDestroyed
+            | WorldStateChange::Stalemate => break,
+            WorldStateChange::NoChange => {}
+        }
+    }
+
+    world.stop();
+    run
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world_data_size;
+    use world_macros::puzzle;
+
+    /// GIVEN a puzzle with one down arrow in stock
+    /// WHEN all solutions are searched for
+    /// THEN the only minimal solution turns the mouse towards the rocket
+    #[test]
+    fn find_all_single_arrow() {
+        let data = puzzle!("Win", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>          Av                                             │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+        let world = World::load(data);
+
+        let report = find_all_solutions(
+            &world,
+            SolverBudget {
+                max_ticks: 2000,
+                max_nodes: 100,
+            },
+        );
+
+        assert!(report.complete);
+        assert_eq!(
+            vec![Solution {
+                arrows: vec![PlacedArrow {
+                    x: 2,
+                    y: 0,
+                    direction: Direction::Down
+                }],
+                ticks: 180,
+            }],
+            report.solutions
+        );
+    }
+
+    /// GIVEN a puzzle where an up arrow in the mouse's path changes nothing, and a down arrow
+    /// further along wins
+    /// WHEN all solutions are searched for
+    /// THEN the pair of arrows queued from the up arrow before the down arrow was found to win
+    /// is not reported
+    #[test]
+    fn find_all_skips_queued_supersets() {
+        let data = puzzle!("Superset", "Test",
+        "┌────────────────────────┐"
+        "│M>                      │"
+        "│                        │"
+        "│          R      A^     │"
+        "│                        │"
+        "│                 Av     │"
+        "└────────────────────────┘");
+        let world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+        let up = PlacedArrow {
+            x: 1,
+            y: 0,
+            direction: Direction::Up,
+        };
+        let down = PlacedArrow {
+            x: 2,
+            y: 0,
+            direction: Direction::Down,
+        };
+
+        let report = find_all_solutions(
+            &world,
+            SolverBudget {
+                max_ticks: 2000,
+                max_nodes: 1000,
+            },
+        );
+
+        assert!(report.complete);
+        assert!(report
+            .solutions
+            .iter()
+            .any(|solution| solution.arrows == [down]));
+        assert!(!report
+            .solutions
+            .iter()
+            .any(|solution| solution.arrows == [up]));
+        for solution in report.solutions.iter() {
+            assert!(!report.solutions.iter().any(|other| other != solution
+                && is_superset_of_solution(core::slice::from_ref(other), &solution.arrows)));
+        }
+    }
+
+    /// GIVEN a puzzle the mouse wins without any arrows
+    /// WHEN the first solution is searched for
+    /// THEN the empty placement is reported after a single node
+    #[test]
+    fn find_first_needs_no_arrows() {
+        let data = puzzle!("Trivial", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>        R           Av                                   │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+        let world = World::load(data);
+
+        let report = find_first_solution(
+            &world,
+            SolverBudget {
+                max_ticks: 2000,
+                max_nodes: 100,
+            },
+        );
+
+        assert_eq!(1, report.nodes);
+        assert_eq!(1, report.solutions.len());
+        assert!(report.solutions[0].arrows.is_empty());
+    }
+
+    /// GIVEN a puzzle with a solution
+    /// WHEN the node budget is too small to reach it
+    /// THEN no solutions are reported and the search is incomplete
+    #[test]
+    fn node_budget_truncates_search() {
+        let data = puzzle!("Win", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>          Av                                             │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+        let world = World::load(data);
+
+        let report = find_all_solutions(
+            &world,
+            SolverBudget {
+                max_ticks: 2000,
+                max_nodes: 1,
+            },
+        );
+
+        assert_eq!(1, report.nodes);
+        assert!(!report.complete);
+        assert!(report.solutions.is_empty());
+    }
+}
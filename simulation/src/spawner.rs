@@ -0,0 +1,168 @@
+use crate::{Direction, WalkerType};
+
+/// The default number of ticks between walkers spawning. This is the time taken for a mouse to
+/// walk a single square, so spawned mice do not overlap
+pub const DEFAULT_SPAWN_INTERVAL: u32 = 60;
+
+/// A spawn point that periodically creates walkers of one type, walking in a set direction
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Spawner {
+    x: usize,
+    y: usize,
+    direction: Direction,
+    walker_type: WalkerType,
+    /// The number of ticks between walkers spawning
+    interval: u32,
+    /// The number of ticks until the next walker spawns
+    countdown: u32,
+}
+
+impl Spawner {
+    /// Creates a new spawner, which first spawns a walker after the default interval
+    /// #examples
+    /// ```
+    /// use simulation::{Spawner, Direction, WalkerType};
+    /// let spawner = Spawner::new(0, 0, Direction::Right, WalkerType::Mouse);
+    /// ```
+    pub fn new(x: usize, y: usize, direction: Direction, walker_type: WalkerType) -> Spawner {
+        Spawner {
+            x,
+            y,
+            direction,
+            walker_type,
+            interval: DEFAULT_SPAWN_INTERVAL,
+            countdown: DEFAULT_SPAWN_INTERVAL,
+        }
+    }
+
+    /// Recreates a spawner part way through its countdown, as captured by a snapshot
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate of the spawner
+    /// * `y`: The y coordinate of the spawner
+    /// * `direction`: The direction spawned walkers walk in
+    /// * `walker_type`: The type of walker spawned
+    /// * `interval`: The number of ticks between walkers spawning
+    /// * `countdown`: The number of ticks until the next walker spawns
+    pub(crate) fn restore(
+        x: usize,
+        y: usize,
+        direction: Direction,
+        walker_type: WalkerType,
+        interval: u32,
+        countdown: u32,
+    ) -> Spawner {
+        Spawner {
+            x,
+            y,
+            direction,
+            walker_type,
+            interval,
+            countdown,
+        }
+    }
+
+    /// Gets the x-coordinate of the spawner
+    pub fn get_x(&self) -> usize {
+        self.x
+    }
+
+    /// Gets the y-coordinate of the spawner
+    pub fn get_y(&self) -> usize {
+        self.y
+    }
+
+    /// Gets the direction spawned walkers walk in
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Gets the type of walker spawned
+    pub fn get_type(&self) -> WalkerType {
+        self.walker_type
+    }
+
+    /// Gets the number of ticks between walkers spawning
+    pub fn get_interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Gets the number of ticks until the next walker spawns
+    pub fn get_countdown(&self) -> u32 {
+        self.countdown
+    }
+
+    /// Sets the number of ticks between walkers spawning. A pending spawn is brought forward if
+    /// the new interval is shorter
+    ///
+    /// Arguments:
+    /// * `interval`: The number of ticks between walkers spawning. Must be at least 1
+    pub(crate) fn set_interval(&mut self, interval: u32) {
+        assert!(interval > 0);
+
+        self.interval = interval;
+        self.countdown = self.countdown.min(interval);
+    }
+
+    /// Restarts the countdown, so that the next walker spawns after a full interval
+    pub(crate) fn reset(&mut self) {
+        self.countdown = self.interval;
+    }
+
+    /// Advances the countdown by a single step
+    ///
+    /// Return value:
+    /// True if the countdown ran out, and a walker should be spawned
+    pub(crate) fn advance(&mut self) -> bool {
+        self.countdown -= 1;
+        if self.countdown > 0 {
+            return false;
+        }
+
+        self.countdown = self.interval;
+        true
+    }
+}
+
+/// Makes every spawner create walkers of one type at a fixed interval, regardless of their own
+/// type and interval. Used by battle events
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct SpawnFlood {
+    /// The type of walker every spawner creates
+    pub(crate) walker_type: WalkerType,
+    /// The number of ticks between walkers spawning
+    pub(crate) interval: u32,
+    /// The number of ticks until the next walkers spawn
+    pub(crate) countdown: u32,
+}
+
+impl SpawnFlood {
+    /// Creates a flood, which first spawns walkers after a full interval
+    ///
+    /// Arguments:
+    /// * `walker_type`: The type of walker every spawner creates
+    /// * `interval`: The number of ticks between walkers spawning. Must be at least 1
+    pub(crate) fn new(walker_type: WalkerType, interval: u32) -> SpawnFlood {
+        assert!(interval > 0);
+
+        SpawnFlood {
+            walker_type,
+            interval,
+            countdown: interval,
+        }
+    }
+
+    /// Advances the countdown by a single step
+    ///
+    /// Return value:
+    /// True if every spawner should create a walker
+    pub(crate) fn advance(&mut self) -> bool {
+        self.countdown -= 1;
+        if self.countdown > 0 {
+            return false;
+        }
+
+        self.countdown = self.interval;
+        true
+    }
+}
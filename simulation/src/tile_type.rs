@@ -6,6 +6,7 @@ use core::convert::TryFrom;
 pub enum TileType {
     Empty,
     Rocket,
+    RocketDestroyed,
     Hole,
     Up,
     UpHalf,
@@ -22,7 +23,7 @@ impl TileType {
     /// unaffected
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{TileType};
+    /// use simulation::{TileType};
     /// TileType::Up.diminish();
     /// ```
     pub fn diminish(self) -> TileType {
@@ -39,13 +40,30 @@ impl TileType {
     }
 }
 
+impl From<Direction> for TileType {
+    /// Converts a direction to the corresponding full size arrow
+    /// #examples
+    /// ```
+    /// use simulation::{TileType, Direction};
+    /// assert_eq!(TileType::Up, TileType::from(Direction::Up));
+    /// ```
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => TileType::Up,
+            Direction::Down => TileType::Down,
+            Direction::Left => TileType::Left,
+            Direction::Right => TileType::Right,
+        }
+    }
+}
+
 impl TryFrom<TileType> for Direction {
     type Error = ();
 
     /// Converts the arrow type to the corresponding Direction
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{TileType, Direction};
+    /// use simulation::{TileType, Direction};
     /// use std::convert::TryInto;
     /// let direction: Direction = TileType::Up.try_into().unwrap();
     /// ```
@@ -55,7 +73,9 @@ impl TryFrom<TileType> for Direction {
             TileType::Down | TileType::DownHalf => Ok(Direction::Down),
             TileType::Left | TileType::LeftHalf => Ok(Direction::Left),
             TileType::Right | TileType::RightHalf => Ok(Direction::Right),
-            TileType::Empty | TileType::Rocket | TileType::Hole => Err(()),
+            TileType::Empty | TileType::Rocket | TileType::RocketDestroyed | TileType::Hole => {
+                Err(())
+            }
         }
     }
 }
@@ -75,6 +95,17 @@ mod test {
         assert_eq!(TileType::RightHalf, TileType::Right.diminish());
     }
 
+    /// GIVEN the four ordinal directions
+    /// WHEN they are converted to tiles
+    /// THEN full size arrows are output
+    #[test]
+    fn from_direction() {
+        assert_eq!(TileType::Up, TileType::from(Direction::Up));
+        assert_eq!(TileType::Down, TileType::from(Direction::Down));
+        assert_eq!(TileType::Left, TileType::from(Direction::Left));
+        assert_eq!(TileType::Right, TileType::from(Direction::Right));
+    }
+
     /// GIVEN A half arrow
     /// WHEN we diminish it
     /// THEN an empty is output
@@ -86,13 +117,14 @@ mod test {
         assert_eq!(TileType::Empty, TileType::RightHalf.diminish());
     }
 
-    /// GIVEN an empty/hole/rocket
+    /// GIVEN an empty/hole/rocket/destroyed rocket
     /// WHEN we diminish it
-    /// THEN an empty is output
+    /// THEN the tile is unchanged
     #[test]
     fn diminish_other() {
         assert_eq!(TileType::Empty, TileType::Empty.diminish());
         assert_eq!(TileType::Hole, TileType::Hole.diminish());
         assert_eq!(TileType::Rocket, TileType::Rocket.diminish());
+        assert_eq!(TileType::RocketDestroyed, TileType::RocketDestroyed.diminish());
     }
 }
@@ -0,0 +1,233 @@
+use crate::{World, WorldStateChange};
+
+/// The ways a verification run can end
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VerificationOutcome {
+    /// All mice were rescued
+    Win,
+    /// A mouse was lost, a rocket destroyed, or the solution could not be placed
+    Lose,
+    /// The walkers began looping, so the puzzle would never be won or lost
+    Stalemate,
+    /// The tick budget ran out before the puzzle was won or lost
+    Timeout,
+}
+
+/// The result of verifying a puzzle
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Verification {
+    /// How the run ended
+    pub outcome: VerificationOutcome,
+    /// The number of ticks that were run
+    pub ticks: u32,
+}
+
+/// Places the solution arrows embedded in a puzzle and runs the simulation without rendering
+/// until the puzzle is won, lost or stalemated, or the tick budget runs out
+///
+/// Arguments:
+/// * `world`: The loaded puzzle, of any size. This is copied, so is left unchanged
+/// * `max_ticks`: The maximum number of ticks to run
+///
+/// Return value:
+/// The outcome of the run and the number of ticks taken
+///
+/// #examples
+/// ```
+/// use simulation::{verify_solution, VerificationOutcome, World};
+/// let verification = verify_solution(&World::load([0; 199]), 100);
+/// assert_eq!(VerificationOutcome::Timeout, verification.outcome);
+/// ```
+pub fn verify_solution<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const TILES: usize,
+    const DATA: usize,
+>(
+    world: &World<WIDTH, HEIGHT, TILES, DATA>,
+    max_ticks: u32,
+) -> Verification {
+    let mut world = world.clone();
+    if world.place_solution().is_err() {
+        return Verification {
+            outcome: VerificationOutcome::Lose,
+            ticks: 0,
+        };
+    }
+
+    world.start();
+    for tick in 1..=max_ticks {
+        let outcome = match world.tick() {
+            WorldStateChange::Win => VerificationOutcome::Win,
+            WorldStateChange::Lose | WorldStateChange::RocketDestroyed => VerificationOutcome::Lose,
+            WorldStateChange::Stalemate => VerificationOutcome::Stalemate,
+            WorldStateChange::NoChange => continue,
+        };
+
+        return Verification {
+            outcome,
+            ticks: tick,
+        };
+    }
+
+    Verification {
+        outcome: VerificationOutcome::Timeout,
+        ticks: max_ticks,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world_data_size;
+    use world_macros::puzzle;
+
+    /// GIVEN a puzzle where the solution arrow directs a mouse into a rocket
+    /// WHEN it is verified
+    /// THEN it is won as soon as the mouse reaches the rocket
+    #[test]
+    fn verify_win() {
+        let data = puzzle!("Win", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>          Av                                             │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+
+        let verification = verify_solution(&World::load(data), 1000);
+
+        assert_eq!(VerificationOutcome::Win, verification.outcome);
+        assert_eq!(180, verification.ticks);
+    }
+
+    /// GIVEN a 5x3 puzzle where the mouse follows the edge walls into a rocket
+    /// WHEN it is verified
+    /// THEN it is won as soon as the mouse reaches the rocket
+    #[test]
+    fn verify_small_world() {
+        let data = puzzle!("Small", "Test",
+        "┌────────────────────────┐"
+        "│M>                      │"
+        "│                        │"
+        "│                        │"
+        "│                        │"
+        "│                    R   │"
+        "└────────────────────────┘");
+        let world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        let verification = verify_solution(&world, 1000);
+
+        assert_eq!(VerificationOutcome::Win, verification.outcome);
+        assert_eq!(360, verification.ticks);
+    }
+
+    /// GIVEN a puzzle where the mouse walks into a hole
+    /// WHEN it is verified
+    /// THEN it is lost as soon as the mouse reaches the hole
+    #[test]
+    fn verify_lose() {
+        let data = puzzle!("Lose", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>   H                                                     │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+
+        let verification = verify_solution(&World::load(data), 1000);
+
+        assert_eq!(VerificationOutcome::Lose, verification.outcome);
+        assert_eq!(60, verification.ticks);
+    }
+
+    /// GIVEN a puzzle where the mouse circles the edge without reaching the rocket
+    /// WHEN it is verified
+    /// THEN the tick budget runs out
+    #[test]
+    fn verify_timeout() {
+        let data = puzzle!("Timeout", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>                                                         │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+
+        let verification = verify_solution(&World::load(data), 1000);
+
+        assert_eq!(VerificationOutcome::Timeout, verification.outcome);
+        assert_eq!(1000, verification.ticks);
+    }
+
+    /// GIVEN a puzzle where the mouse circles the edge without reaching the rocket
+    /// WHEN it is verified with a large tick budget
+    /// THEN the loop is found before the budget runs out
+    #[test]
+    fn verify_stalemate() {
+        let data = puzzle!("Stalemate", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>                                                         │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+
+        let verification = verify_solution(&World::load(data), 100_000);
+
+        assert_eq!(VerificationOutcome::Stalemate, verification.outcome);
+        assert!(verification.ticks < 100_000);
+    }
+}
@@ -1,6 +1,21 @@
 use super::Direction;
 use super::FixedPoint;
 
+/// The number of steps a mouse takes to cross a square
+const MOUSE_STEPS_PER_SQUARE: i16 = 60;
+/// The number of steps a cat takes to cross a square
+const CAT_STEPS_PER_SQUARE: i16 = 90;
+/// The number of fractional units in a square
+const UNITS_PER_SQUARE: i16 = <FixedPoint>::FRACTIONAL_UNITS;
+/// The number of fractional units from the edge of a square to its centre
+const UNITS_TO_CENTRE: i16 = UNITS_PER_SQUARE / 2;
+const _: () = assert!(
+    UNITS_PER_SQUARE % MOUSE_STEPS_PER_SQUARE == 0
+        && UNITS_PER_SQUARE % CAT_STEPS_PER_SQUARE == 0
+        && UNITS_PER_SQUARE % 2 == 0,
+    "Walkers must take a whole number of fractional units per step, and squares need a centre"
+);
+
 /// Type of walker. This determines how fast they move
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum WalkerType {
@@ -12,8 +27,10 @@ pub enum WalkerType {
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum WalkResult {
     None,
-    NewSquare, // TODO: Figure out other significant thresholds where cats and mice can collide
-               // e.g. if walking towards each other, away from each other, at right angles etc
+    // Collisions between cats and mice can happen anywhere in a square, so are not reported here.
+    // See World::check_collisions for the distance based rules
+    /// The walker reached the centre of a new square
+    NewSquare,
 }
 
 /// The overall state of the walker
@@ -24,8 +41,37 @@ pub enum WalkerState {
     Rescued,
 }
 
+/// Identifies a walker from its creation until it is removed from the world, however the
+/// walkers around it come and go
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct WalkerId(u32);
+
+impl WalkerId {
+    /// Creates an ID from its numeric value
+    /// #examples
+    /// ```
+    /// use simulation::WalkerId;
+    /// assert_eq!(7, WalkerId::new(7).get_value());
+    /// ```
+    pub fn new(value: u32) -> WalkerId {
+        WalkerId(value)
+    }
+
+    /// Gets the numeric value of the ID
+    pub fn get_value(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets the ID that follows this one
+    pub(crate) fn next(self) -> WalkerId {
+        WalkerId(self.0.wrapping_add(1))
+    }
+}
+
 /// A walker. This can be a cat or a mouse
+#[derive(Clone)]
 pub struct Walker {
+    id: WalkerId,
     x: FixedPoint,
     y: FixedPoint,
     direction: Direction,
@@ -34,65 +80,170 @@ pub struct Walker {
 }
 
 impl Walker {
-    /// Creates a new walker
+    /// Creates a new walker, standing at the centre of a square
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{Walker, Direction, WalkerType};
-    /// let walker = Walker::new(0, 0, Direction::Right, WalkerType::Mouse);
+    /// use simulation::{Walker, WalkerId, Direction, WalkerType};
+    /// let walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Mouse);
     /// ```
-    pub fn new(x: i8, y: i8, direction: Direction, walker_type: WalkerType) -> Walker {
+    pub fn new(
+        id: WalkerId,
+        x: i8,
+        y: i8,
+        direction: Direction,
+        walker_type: WalkerType,
+    ) -> Walker {
         Walker {
-            x: FixedPoint::new(x, 0),
-            y: FixedPoint::new(y, 0),
+            id,
+            x: FixedPoint::new(x, UNITS_TO_CENTRE),
+            y: FixedPoint::new(y, UNITS_TO_CENTRE),
             direction,
             walker_type,
             walker_state: WalkerState::Alive,
         }
     }
 
-    /// Advances the position of a walker
+    /// Recreates a walker part way through a walk, as captured by a snapshot
+    ///
+    /// Arguments:
+    /// * `id`: The ID of the walker
+    /// * `x`: The x coordinate of the walker
+    /// * `y`: The y coordinate of the walker
+    /// * `direction`: The walk direction of the walker
+    /// * `walker_type`: The type of walker
+    /// * `walker_state`: The state of the walker
+    pub(crate) fn restore(
+        id: WalkerId,
+        x: FixedPoint,
+        y: FixedPoint,
+        direction: Direction,
+        walker_type: WalkerType,
+        walker_state: WalkerState,
+    ) -> Walker {
+        Walker {
+            id,
+            x,
+            y,
+            direction,
+            walker_type,
+            walker_state,
+        }
+    }
+
+    /// Gets the usual speed of a type of walker, per step. Mice take 60 steps to cross a square,
+    /// and cats 90
+    /// #examples
+    /// ```
+    /// use simulation::{Walker, WalkerType, FixedPoint};
+    /// assert!(Walker::default_speed(WalkerType::Mouse) == FixedPoint::new(0, 6));
+    /// ```
+    pub fn default_speed(walker_type: WalkerType) -> FixedPoint {
+        match walker_type {
+            WalkerType::Cat => FixedPoint::new(0, UNITS_PER_SQUARE / CAT_STEPS_PER_SQUARE),
+            WalkerType::Mouse => FixedPoint::new(0, UNITS_PER_SQUARE / MOUSE_STEPS_PER_SQUARE),
+        }
+    }
+
+    /// Advances the position of a walker at the usual speed for its type
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{Walker, Direction, WalkerType};
-    /// let mut walker = Walker::new(0, 0, Direction::Right, WalkerType::Mouse);
+    /// use simulation::{Walker, WalkerId, Direction, WalkerType};
+    /// let mut walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Mouse);
     /// let walk_result = walker.walk();
     /// ```
     pub fn walk(&mut self) -> WalkResult {
-        // Determine the speed of the walker
-        let speed = match self.walker_type {
-            WalkerType::Cat => FixedPoint::new(0, 4),
-            WalkerType::Mouse => FixedPoint::new(0, 6),
+        self.walk_at(Self::default_speed(self.walker_type))
+    }
+
+    /// Advances the position of a walker at a given speed. The walker never walks past the centre
+    /// of the next square, so that it stops exactly on every centre even if the speed changes
+    /// part way across
+    ///
+    /// Arguments:
+    /// * `speed`: The distance to walk. Must be less than a whole square
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{Walker, WalkerId, Direction, WalkerType, FixedPoint};
+    /// let mut walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Mouse);
+    /// let walk_result = walker.walk_at(FixedPoint::new(0, 12));
+    /// ```
+    pub fn walk_at(&mut self, speed: FixedPoint) -> WalkResult {
+        // Limit the speed to the distance left to the next square
+        let position = match self.direction {
+            Direction::Up | Direction::Down => self.y,
+            Direction::Left | Direction::Right => self.x,
+        };
+        let units_per_square = UNITS_PER_SQUARE as i32;
+        let past_centre = Self::past_centre(position);
+        let remaining = match self.direction {
+            Direction::Up | Direction::Left if past_centre == 0 => units_per_square,
+            Direction::Up | Direction::Left => past_centre,
+            Direction::Down | Direction::Right => units_per_square - past_centre,
         };
+        let speed = speed.min(FixedPoint::from_scaled(remaining));
 
-        // Advance the position and determine if a new grid was reached
-        let reached_new_square = match self.direction {
+        // Advance the position. As the walk stops on every centre, a new square is reached when
+        // the walker is back on a centre
+        let position = match self.direction {
             Direction::Up => {
-                let start = self.y;
                 self.y -= speed;
-                self.y.did_overflow(start)
+                self.y
             }
             Direction::Down => {
-                let start = self.y;
                 self.y += speed;
-                self.y.did_overflow(start)
+                self.y
             }
             Direction::Left => {
-                let start = self.x;
                 self.x -= speed;
-                self.x.did_overflow(start)
+                self.x
             }
             Direction::Right => {
-                let start = self.x;
                 self.x += speed;
-                self.x.did_overflow(start)
+                self.x
             }
         };
 
-        return if reached_new_square {
+        if Self::past_centre(position) == 0 {
             WalkResult::NewSquare
         } else {
             WalkResult::None
-        };
+        }
+    }
+
+    /// Gets how far a coordinate is past the centre of the square before it
+    ///
+    /// Arguments:
+    /// * `position`: The coordinate
+    ///
+    /// Return value:
+    /// The distance in fractional units, in the range 0 to less than a square
+    fn past_centre(position: FixedPoint) -> i32 {
+        (position.scaled() - UNITS_TO_CENTRE as i32).rem_euclid(UNITS_PER_SQUARE as i32)
+    }
+
+    /// Moves a walker whose centre has walked off the edge of the world to the opposite edge
+    ///
+    /// Arguments:
+    /// * `width`: The width of the world
+    /// * `height`: The height of the world
+    pub(crate) fn wrap(&mut self, width: i8, height: i8) {
+        if self.x.floor() < 0 {
+            self.x += FixedPoint::new(width, 0);
+        } else if self.x.floor() >= width {
+            self.x -= FixedPoint::new(width, 0);
+        }
+
+        if self.y.floor() < 0 {
+            self.y += FixedPoint::new(height, 0);
+        } else if self.y.floor() >= height {
+            self.y -= FixedPoint::new(height, 0);
+        }
+    }
+
+    /// Gets the ID of the walker
+    pub fn get_id(&self) -> WalkerId {
+        self.id
     }
 
     /// Gets the type of walker
@@ -147,7 +298,7 @@ mod tests {
     /// THEN it reaches a new square on the 60th, 120th and 180th walk cycle
     #[test]
     fn mouse_walker_indicates_new_square() {
-        let mut walker = Walker::new(0, 0, Direction::Right, WalkerType::Mouse);
+        let mut walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Mouse);
 
         for step in 1..=180 {
             if step % 60 == 0 {
@@ -158,12 +309,29 @@ mod tests {
         }
     }
 
+    /// GIVEN a mouse that has walked part way across a square
+    /// WHEN it walks on at a speed that does not divide the rest of the square
+    /// THEN the last step is shortened so that it stops exactly on the centre of the next square
+    #[test]
+    fn walk_at_stops_on_square() {
+        let mut walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Left, WalkerType::Mouse);
+        for _ in 0..5 {
+            walker.walk();
+        }
+
+        for _ in 0..27 {
+            assert_eq!(WalkResult::None, walker.walk_at(FixedPoint::new(0, 12)));
+        }
+        assert_eq!(WalkResult::NewSquare, walker.walk_at(FixedPoint::new(0, 12)));
+        assert_eq!(FixedPoint::new(0, -180), walker.get_x());
+    }
+
     /// GIVEN a cat at 0,0
     /// WHEN it walks right 180 times
     /// THEN it reaches a new square on the 90th and 180th walk cycle
     #[test]
     fn cat_walker_indicates_new_square() {
-        let mut walker = Walker::new(0, 0, Direction::Right, WalkerType::Cat);
+        let mut walker = Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Cat);
 
         for step in 1..=180 {
             if step % 90 == 0 {
@@ -0,0 +1,11 @@
+/// The number of walkers of one type that are in the world, have been rescued and have died
+/// since the world was last reset
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct WalkerCounts {
+    /// The walkers still walking
+    pub alive: usize,
+    /// The walkers that entered a rocket. Only mice are rescued
+    pub rescued: usize,
+    /// The walkers that fell into a hole, were eaten or were lost entering a rocket
+    pub dead: usize,
+}
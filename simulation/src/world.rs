@@ -1,35 +1,77 @@
+use crate::loop_detector::{LoopDetector, StateHasher};
+use crate::owned_arrow::OwnedArrow;
+use crate::snapshot::{SnapshotReader, SnapshotWriter, SNAPSHOT_MAGIC};
+use crate::spawner::SpawnFlood;
 use crate::{
-    walker::{WalkResult},
-    ArrowStock, Direction, TileType, Walker, WalkerState, WalkerType, WorldStateChange,
+    walker::WalkResult, ArrowStock, DeathCause, DiminishPolicy, Direction, EventSink, FixedPoint,
+    LossCondition, PlacementError, Player, Rng, RocketEvent, RocketEventKind, RocketPenalty,
+    Ruleset, SnapshotError, Spawner, TileType, Turn, Walker, WalkerCounts, WalkerId, WalkerState,
+    WalkerType, WinCondition, WorldEvent, WorldState, WorldStateChange,
 };
 use arrayvec::ArrayVec;
 use core::convert::TryInto;
 
-/// The width of the world
+/// The width of the default world
 pub const WORLD_WIDTH: usize = 12;
-/// The height of the world
+/// The height of the default world
 pub const WORLD_HEIGHT: usize = 9;
-/// The maximum number of walkers, if all squares were filled with walkers
-const MAX_WALKERS: usize = WORLD_WIDTH * WORLD_HEIGHT;
-/// The maximum number of tiles, if all squares were filled with tiles
-const MAX_TILES: usize = WORLD_WIDTH * WORLD_HEIGHT;
+/// The number of tiles in the default world
+pub(crate) const MAX_TILES: usize = WORLD_WIDTH * WORLD_HEIGHT;
+/// The size of the serialised default world
+pub(crate) const DATA_SIZE: usize = world_data_size(WORLD_WIDTH, WORLD_HEIGHT);
 /// The map author field
-const MAP_NAME_SIZE: usize = 32;
-const MAP_NAME_OFFSET: usize = 0;
+pub(crate) const MAP_NAME_SIZE: usize = 32;
+pub(crate) const MAP_NAME_OFFSET: usize = 0;
 /// The map name field
-const MAP_AUTHOR_SIZE: usize = 32;
-const MAP_AUTHOR_OFFSET: usize = MAP_NAME_OFFSET + MAP_NAME_SIZE;
-/// The size of the wall block
-const WALL_BLOCK_SIZE: usize = 27;
+pub(crate) const MAP_AUTHOR_SIZE: usize = 32;
+pub(crate) const MAP_AUTHOR_OFFSET: usize = MAP_NAME_OFFSET + MAP_NAME_SIZE;
+/// The offset of the wall block. Its size depends on the world dimensions
 const WALL_BLOCK_OFFSET: usize = MAP_AUTHOR_OFFSET + MAP_AUTHOR_SIZE;
-/// The size and offset of the tile block
-const TILE_BLOCK_SIZE: usize = 108;
-const TILE_BLOCK_OFFSET: usize = WALL_BLOCK_OFFSET + WALL_BLOCK_SIZE;
+/// The largest supported width or height, so that coordinates fit in a FixedPoint
+const MAX_DIMENSION: usize = i8::MAX as usize;
+/// The size of a serialised walker: ID, two fixed point coordinates, then direction, type and
+/// state
+const SNAPSHOT_WALKER_SIZE: usize = 4 + 3 + 3 + 1 + 1 + 1;
+/// The size of a serialised rocket event: coordinates and kind
+const SNAPSHOT_ROCKET_EVENT_SIZE: usize = 3;
+/// The size of a serialised ruleset: two speeds, the turn priority, the diminish policy, the
+/// rocket penalty, then the win and loss conditions with their counts
+const SNAPSHOT_RULESET_SIZE: usize = 3 + 3 + 3 + 1 + 1 + (1 + 4) * 2;
+/// The size of a spawner in a snapshot
+const SNAPSHOT_SPAWNER_SIZE: usize = 1 + 1 + 1 + 1 + 4 + 4;
+/// The size of a spawn flood in a snapshot
+const SNAPSHOT_SPAWN_FLOOD_SIZE: usize = 1 + 4 + 4;
+/// The size of the loop detector in a snapshot: whether a configuration is stored, its hash,
+/// then Brent's power and length
+const SNAPSHOT_LOOP_DETECTOR_SIZE: usize = 1 + 8 + 4 + 4;
+/// The size of an arrow placed by a player in a snapshot
+const SNAPSHOT_OWNED_ARROW_SIZE: usize = 1 + 1 + 1 + 4 + 4;
+/// The largest possible snapshot of the default world
+pub const SNAPSHOT_MAX_SIZE: usize = <World>::MAX_SNAPSHOT_SIZE;
 /// The masks used to pack the left walls. There are four walls packed into each byte
 const LEFT_WALL_MASK: [u8; 4] = [0b00000010, 0b00001000, 0b00100000, 0b10000000];
 /// The masks uses to pack the top walls.
 const TOP_WALL_MASK: [u8; 4] = [0b00000001, 0b00000100, 0b00010000, 0b01000000];
 
+/// Gets the size of a serialised world of the given dimensions: the name and author, followed
+/// by two bits of walls per square, then one byte per square
+///
+/// Arguments:
+/// * `width`: The width of the world
+/// * `height`: The height of the world
+///
+/// Return value:
+/// The number of bytes needed to hold the world
+///
+/// #examples
+/// ```
+/// use simulation::world_data_size;
+/// assert_eq!(199, world_data_size(12, 9));
+/// ```
+pub const fn world_data_size(width: usize, height: usize) -> usize {
+    WALL_BLOCK_OFFSET + (width * height).div_ceil(4) + width * height
+}
+
 const ENTITY_TYPE_MASK: u8 = 0b11100000u8;
 const ENTITY_DIRECTION_MASK: u8 = 0b00011000u8;
 const ARROW_PRESENT_MASK: u8 = 0b00000100u8;
@@ -40,6 +82,8 @@ const ENTITY_TYPE_MOUSE: u8 = 0b00100000;
 const ENTITY_TYPE_CAT: u8 = 0b01000000;
 const ENTITY_TYPE_ROCKET: u8 = 0b01100000;
 const ENTITY_TYPE_HOLE: u8 = 0b10000000;
+const ENTITY_TYPE_MOUSE_SPAWNER: u8 = 0b10100000;
+const ENTITY_TYPE_CAT_SPAWNER: u8 = 0b11000000;
 
 const ENTITY_DIRECTION_UP: u8 = 0b00000000;
 const ENTITY_DIRECTION_DOWN: u8 = 0b00001000;
@@ -51,9 +95,22 @@ const ARROW_DIRECTION_DOWN: u8 = 0b00000001;
 const ARROW_DIRECTION_LEFT: u8 = 0b00000010;
 const ARROW_DIRECTION_RIGHT: u8 = 0b00000011;
 
+/// The number of simulation steps run per tick when running fast
+const FAST_FORWARD_STEPS: usize = 3;
+
+/// The number of fractional units in a square
+const UNITS_PER_SQUARE: i32 = <FixedPoint>::FRACTIONAL_UNITS as i32;
+/// The distance at which a cat and mouse walking towards each other collide: half a square
+const COLLISION_HEAD_ON: i32 = UNITS_PER_SQUARE / 2;
+/// The distance at which a cat and mouse walking the same direction collide: a third of a square
+const COLLISION_SAME_DIRECTION: i32 = UNITS_PER_SQUARE / 3;
+/// The distance on both axes at which a cat and mouse walking at right angles collide: a third
+/// of a square
+const COLLISION_RIGHT_ANGLE: i32 = UNITS_PER_SQUARE / 3;
+
 /// Represents the entire state of a world
-/// This is a 12x9 array of squares. Each square controls the top and left walls,
-/// and can have one of a cat, mouse or rocket in it. This information is packed
+/// This is a WIDTH x HEIGHT array of squares, 12x9 by default. Each square controls the top and
+/// left walls, and can have one of a cat, mouse or rocket in it. This information is packed
 /// to minimise space
 /// struct Header_t
 /// {
@@ -61,7 +118,8 @@ const ARROW_DIRECTION_RIGHT: u8 = 0b00000011;
 ///   char[32] author;
 /// }
 ///
-/// Followed by 27 of the following
+/// Followed by (TILES + 3) / 4 of the following, where TILES is WIDTH * HEIGHT. Squares are
+/// packed row by row, and the bits for squares past the last are unused
 /// {
 ///   uint8_t: 1 wall_up_0;
 ///   uint8_t: 1 wall_left_0;
@@ -71,30 +129,62 @@ const ARROW_DIRECTION_RIGHT: u8 = 0b00000011;
 ///   uint8_t: 1 wall_left_2;
 ///   uint8_t: 1 wall_up_3;
 ///   uint8_t: 1 wall_left_3;
-/// }[27]
+/// }[(TILES + 3) / 4]
 ///
-/// Followed by 108 of the following
+/// Followed by TILES of the following, row by row
 /// {
-///   uint8_t: 3 entity; // 0 -> empty, 1 -> mouse, 2 -> cat, 3 -> rocket, 4 -> hole, 5-7 -> unused
+///   uint8_t: 3 entity; // 0 -> empty, 1 -> mouse, 2 -> cat, 3 -> rocket, 4 -> hole, 5 -> mouse spawner, 6 -> cat spawner, 7 -> unused
 ///   uint8_t: 2 entity_direction; // 0 -> up, 1 -> down, 2 -> left, 3 -> right
 ///   uint8_t: 1 arrow; // 0 -> empty, 1 -> arrow
 ///   uint8_t: 2 arrow_direction; // 0 -> up, 1 -> down, 2 -> left, 3 -> right
 /// }
-/// For a total of 64 + 27 + 108 bytes = 199 bytes
-/// TODO: More constants!
-pub struct World {
-    data: [u8; 199],
-    mice: ArrayVec<Walker, MAX_WALKERS>,
-    cats: ArrayVec<Walker, MAX_WALKERS>,
-    tiles: [TileType; MAX_TILES],
+/// For a total of DATA = world_data_size(WIDTH, HEIGHT) bytes, which is 64 + 27 + 108 = 199
+/// bytes for the default 12x9 world
+///
+/// As array lengths cannot yet be computed from generic parameters, TILES and DATA must be given
+/// alongside WIDTH and HEIGHT. These are checked at compile time
+#[derive(Clone)]
+pub struct World<
+    const WIDTH: usize = WORLD_WIDTH,
+    const HEIGHT: usize = WORLD_HEIGHT,
+    const TILES: usize = MAX_TILES,
+    const DATA: usize = DATA_SIZE,
+> {
+    data: [u8; DATA],
+    mice: ArrayVec<Walker, TILES>,
+    cats: ArrayVec<Walker, TILES>,
+    tiles: [TileType; TILES],
+    /// The tiles as they were before the simulation diminished arrows or destroyed rockets
+    placed_tiles: [TileType; TILES],
+    /// The owners of arrows placed by players during battles, and how long they have left
+    arrow_owners: [Option<OwnedArrow>; TILES],
+    /// The sequence number of the next arrow placed by a player
+    arrow_sequence: u32,
     arrow_stock: ArrowStock,
+    ruleset: Ruleset,
+    rocket_events: ArrayVec<RocketEvent, TILES>,
+    spawners: ArrayVec<Spawner, TILES>,
+    /// Overrides every spawner while set
+    spawn_flood: Option<SpawnFlood>,
+    /// The seed the random number generator restarts from on reset
+    seed: u32,
+    rng: Rng,
+    /// The ID given to the next walker created
+    next_walker_id: WalkerId,
+    /// The mice rescued and killed since the last reset. Alive is not used
+    removed_mice: WalkerCounts,
+    /// The cats killed since the last reset. Alive is not used
+    removed_cats: WalkerCounts,
+    /// Spots the world returning to a configuration it has been in before
+    loop_detector: LoopDetector,
+    state: WorldState,
 }
 
 impl World {
-    /// Creates a new world with walls around the edge
+    /// Creates a new 12x9 world with walls around the edge
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{World, Direction};
+    /// use simulation::{World, Direction};
     /// let world = World::new();
     /// assert_eq!(true,  world.get_wall(0, 0, Direction::Up));
     /// assert_eq!(false, world.get_wall(0, 0, Direction::Down));
@@ -102,96 +192,222 @@ impl World {
     /// assert_eq!(false, world.get_wall(0, 0, Direction::Right));
     /// ```
     pub fn new() -> World {
+        Self::new_sized()
+    }
+
+    /// Loads a 12x9 world from serialised state. Arrows in the serialised state are the solution,
+    /// so are added to the stock of arrows rather than placed
+    pub fn load(data: [u8; DATA_SIZE]) -> World {
+        Self::load_sized(data)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const TILES: usize, const DATA: usize>
+    World<WIDTH, HEIGHT, TILES, DATA>
+{
+    /// The size of the wall block, with four squares packed into each byte
+    const WALL_BLOCK_SIZE: usize = TILES.div_ceil(4);
+    /// The offset of the tile block
+    const TILE_BLOCK_OFFSET: usize = WALL_BLOCK_OFFSET + Self::WALL_BLOCK_SIZE;
+    /// Fails to compile if the generic parameters describe an impossible world
+    const DIMENSIONS_VALID: () = assert!(
+        WIDTH > 0
+            && HEIGHT > 0
+            && WIDTH <= MAX_DIMENSION
+            && HEIGHT <= MAX_DIMENSION
+            && TILES == WIDTH * HEIGHT
+            && DATA == world_data_size(WIDTH, HEIGHT),
+        "World dimensions are inconsistent"
+    );
+    /// The largest number of mice, and separately of cats, in the world. Spawners stop spawning
+    /// once this is reached
+    pub const MAX_WALKERS: usize = TILES;
+    /// The largest possible snapshot, with every square holding a mouse, a cat, a rocket event,
+    /// a spawner and an arrow placed by a player, and a spawn flood in progress
+    pub const MAX_SNAPSHOT_SIZE: usize = SNAPSHOT_MAGIC.len()
+        + 1
+        + 2
+        + DATA
+        + 1
+        + SNAPSHOT_RULESET_SIZE
+        + 4
+        + TILES * 2
+        + (2 + TILES * SNAPSHOT_WALKER_SIZE) * 2
+        + 2
+        + TILES * SNAPSHOT_ROCKET_EVENT_SIZE
+        + 2
+        + TILES * SNAPSHOT_SPAWNER_SIZE
+        + 4
+        + 2
+        + TILES * SNAPSHOT_OWNED_ARROW_SIZE
+        + 4
+        + 4
+        + 1
+        + SNAPSHOT_SPAWN_FLOOD_SIZE
+        + 4
+        + 4 * 4
+        + SNAPSHOT_LOOP_DETECTOR_SIZE;
+
+    /// Creates a new world of any size with walls around the edge
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, world_data_size};
+    /// let world = World::<4, 3, 12, { world_data_size(4, 3) }>::new_sized();
+    /// assert_eq!(true,  world.get_wall(3, 2, Direction::Right));
+    /// assert_eq!(false, world.get_wall(3, 2, Direction::Left));
+    /// ```
+    pub fn new_sized() -> Self {
+        let () = Self::DIMENSIONS_VALID;
+
         // Create the world
         let mut world = World {
-            data: [0; 199],
+            data: [0; DATA],
             mice: ArrayVec::new(),
             cats: ArrayVec::new(),
-            tiles: [TileType::Empty; MAX_TILES],
+            tiles: [TileType::Empty; TILES],
+            placed_tiles: [TileType::Empty; TILES],
+            arrow_owners: [None; TILES],
+            arrow_sequence: 0,
             arrow_stock: ArrowStock::new(),
+            ruleset: Ruleset::puzzle(),
+            rocket_events: ArrayVec::new(),
+            spawners: ArrayVec::new(),
+            spawn_flood: None,
+            seed: 0,
+            rng: Rng::new(0),
+            next_walker_id: WalkerId::new(0),
+            removed_mice: WalkerCounts::default(),
+            removed_cats: WalkerCounts::default(),
+            loop_detector: LoopDetector::new(),
+            state: WorldState::Stopped,
         };
 
         // Set the walls along the top/left, which also sets the right/bottom
-        for x in 0..WORLD_WIDTH {
+        for x in 0..WIDTH {
             world.set_wall(x, 0, Direction::Up, true);
         }
 
-        for y in 0..WORLD_HEIGHT {
+        for y in 0..HEIGHT {
             world.set_wall(0, y, Direction::Left, true);
         }
 
         world
     }
 
-    /// Loads a world from serialised state
-    pub fn load(data: [u8; 199]) -> World {
+    /// Loads a world of any size from serialised state. Arrows in the serialised state are the
+    /// solution, so are added to the stock of arrows rather than placed
+    pub fn load_sized(data: [u8; DATA]) -> Self {
+        let () = Self::DIMENSIONS_VALID;
+
         // Create the world
         let mut world = World {
             data: data,
             mice: ArrayVec::new(),
             cats: ArrayVec::new(),
-            tiles: [TileType::Empty; MAX_TILES],
+            tiles: [TileType::Empty; TILES],
+            placed_tiles: [TileType::Empty; TILES],
+            arrow_owners: [None; TILES],
+            arrow_sequence: 0,
             arrow_stock: ArrowStock::new(),
+            ruleset: Ruleset::puzzle(),
+            rocket_events: ArrayVec::new(),
+            spawners: ArrayVec::new(),
+            spawn_flood: None,
+            seed: 0,
+            rng: Rng::new(0),
+            next_walker_id: WalkerId::new(0),
+            removed_mice: WalkerCounts::default(),
+            removed_cats: WalkerCounts::default(),
+            loop_detector: LoopDetector::new(),
+            state: WorldState::Stopped,
         };
 
-        // Read the tiles and walkers from the wall data
-        let tile_block = &data[TILE_BLOCK_OFFSET..TILE_BLOCK_OFFSET + TILE_BLOCK_SIZE];
-        for y in 0..WORLD_HEIGHT {
-            for x in 0..WORLD_WIDTH {
-                let entity_type = tile_block[y * WORLD_WIDTH + x] & ENTITY_TYPE_MASK;
-                let entity_direction = match tile_block[y * WORLD_WIDTH + x] & ENTITY_DIRECTION_MASK
-                {
-                    ENTITY_DIRECTION_UP => Direction::Up,
-                    ENTITY_DIRECTION_DOWN => Direction::Down,
-                    ENTITY_DIRECTION_LEFT => Direction::Left,
-                    // The compiler can't figure out that due to the mask this is a
-                    // comprehensive match, so give it a hand
-                    ENTITY_DIRECTION_RIGHT | _ => Direction::Right,
-                };
-                let arrow_present = tile_block[y * WORLD_WIDTH + x] & ARROW_PRESENT_MASK == ARROW_PRESENT_MASK;
-                let arrow_direction = match tile_block[y * WORLD_WIDTH + x] & ARROW_DIRECTION_MASK
-                {
-                    ARROW_DIRECTION_UP => Direction::Up,
-                    ARROW_DIRECTION_DOWN => Direction::Down,
-                    ARROW_DIRECTION_LEFT => Direction::Left,
-                    // The compiler can't figure out that due to the mask this is a
-                    // comprehensive match, so give it a hand
-                    ARROW_DIRECTION_RIGHT | _ => Direction::Right,
-                };
+        // Read the tiles from the wall data
+        let tile_block = &data[Self::TILE_BLOCK_OFFSET..Self::TILE_BLOCK_OFFSET + TILES];
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let entity_type = tile_block[y * WIDTH + x] & ENTITY_TYPE_MASK;
 
-                // Match tiles/walkers
+                // Match tiles and spawners. Walkers are spawned by reset
                 match entity_type {
-                    ENTITY_TYPE_CAT => {
-                        world.create_walker(x, y, entity_direction, WalkerType::Cat);
-                    }
-                    ENTITY_TYPE_MOUSE => {
-                        world.create_walker(x, y, entity_direction, WalkerType::Mouse);
-                    }
                     ENTITY_TYPE_ROCKET => {
                         world.set_tile(x, y, TileType::Rocket);
                     }
                     ENTITY_TYPE_HOLE => {
                         world.set_tile(x, y, TileType::Hole);
                     }
+                    ENTITY_TYPE_MOUSE_SPAWNER | ENTITY_TYPE_CAT_SPAWNER => {
+                        world.spawners.push(Spawner::new(
+                            x,
+                            y,
+                            Self::read_entity_direction(tile_block[y * WIDTH + x]),
+                            if entity_type == ENTITY_TYPE_MOUSE_SPAWNER {
+                                WalkerType::Mouse
+                            } else {
+                                WalkerType::Cat
+                            },
+                        ));
+                    }
                     ENTITY_TYPE_EMPTY | _ => {}
                 }
 
                 // Match arrows
-                if arrow_present {
+                if let Some(arrow_direction) = Self::read_arrow(tile_block[y * WIDTH + x]) {
                     world.arrow_stock[arrow_direction] += 1;
                 }
             }
         }
 
+        world.reset();
         world
     }
 
+    /// Decodes the direction of an entity in the tile block
+    ///
+    /// Arguments:
+    /// * `tile_byte`: The byte from the tile block
+    ///
+    /// Return value:
+    /// The direction of the entity
+    fn read_entity_direction(tile_byte: u8) -> Direction {
+        match tile_byte & ENTITY_DIRECTION_MASK {
+            ENTITY_DIRECTION_UP => Direction::Up,
+            ENTITY_DIRECTION_DOWN => Direction::Down,
+            ENTITY_DIRECTION_LEFT => Direction::Left,
+            // The compiler can't figure out that due to the mask this is a
+            // comprehensive match, so give it a hand
+            ENTITY_DIRECTION_RIGHT | _ => Direction::Right,
+        }
+    }
+
+    /// Decodes the arrow in the tile block
+    ///
+    /// Arguments:
+    /// * `tile_byte`: The byte from the tile block
+    ///
+    /// Return value:
+    /// The direction of the arrow, or None if no arrow is present
+    fn read_arrow(tile_byte: u8) -> Option<Direction> {
+        if tile_byte & ARROW_PRESENT_MASK != ARROW_PRESENT_MASK {
+            return None;
+        }
+
+        Some(match tile_byte & ARROW_DIRECTION_MASK {
+            ARROW_DIRECTION_UP => Direction::Up,
+            ARROW_DIRECTION_DOWN => Direction::Down,
+            ARROW_DIRECTION_LEFT => Direction::Left,
+            // The compiler can't figure out that due to the mask this is a
+            // comprehensive match, so give it a hand
+            ARROW_DIRECTION_RIGHT | _ => Direction::Right,
+        })
+    }
+
     /// Gets the index into the wall data of a particular wall, and the mask required to
     /// get/set it.
     ///
     /// Arguments:
-    /// * `x`: The x coordinate to set. Must be in range 0-11
-    /// * `y`: The y coordinate to set. Must be in range 0-8
+    /// * `x`: The x coordinate to set. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to set. Must be in range 0..HEIGHT
     /// * `direction`: The direction to set
     ///
     /// Return value:
@@ -201,31 +417,33 @@ impl World {
         y: usize,
         direction: Direction,
     ) -> (usize, u8) {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
 
         // Find the affected walls array and position of the requested wall in terms of up/left
-        let (e_x, e_y, mask) = match direction {
-            Direction::Up => (x, y, TOP_WALL_MASK[x & 0x03]),
-            Direction::Down => (x, (y + 1) % WORLD_HEIGHT, TOP_WALL_MASK[x & 0x03]),
-            Direction::Left => (x, y, LEFT_WALL_MASK[x & 0x03]),
-            Direction::Right => ((x + 1) % WORLD_WIDTH, y, LEFT_WALL_MASK[(x + 1) & 0x03]),
+        let (e_x, e_y, masks) = match direction {
+            Direction::Up => (x, y, TOP_WALL_MASK),
+            Direction::Down => (x, (y + 1) % HEIGHT, TOP_WALL_MASK),
+            Direction::Left => (x, y, LEFT_WALL_MASK),
+            Direction::Right => ((x + 1) % WIDTH, y, LEFT_WALL_MASK),
         };
 
-        ((e_y * WORLD_WIDTH + e_x) / 4, mask)
+        // Walls are packed four squares to a byte, in the same order as the tiles
+        let index = e_y * WIDTH + e_x;
+        (index / 4, masks[index & 0x03])
     }
 
     fn set_wall_static(
-        data: &mut [u8; 199],
+        data: &mut [u8; DATA],
         x: usize,
         y: usize,
         direction: Direction,
         present: bool,
     ) {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
 
-        let (wall_index, mask) = World::get_wrapped_wall_index_and_mask(x, y, direction);
+        let (wall_index, mask) = Self::get_wrapped_wall_index_and_mask(x, y, direction);
         let byte = &mut data[WALL_BLOCK_OFFSET + wall_index];
 
         if present {
@@ -238,37 +456,38 @@ impl World {
     /// Sets a wall present/non-present
     ///
     /// Arguments:
-    /// * `x`: The x coordinate to set. Must be in range 0-11
-    /// * `y`: The y coordinate to set. Must be in range 0-8
+    /// * `x`: The x coordinate to set. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to set. Must be in range 0..HEIGHT
     /// * `direction`: The direction to set
     /// * `present`: If the wall should be present
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{World, Direction};
+    /// use simulation::{World, Direction};
     /// let mut world = World::new();
     /// world.set_wall(0, 0, Direction::Down, true);
     /// assert!(world.get_wall(0, 0, Direction::Down));
     /// ```
     pub fn set_wall(&mut self, x: usize, y: usize, direction: Direction, present: bool) {
-        World::set_wall_static(&mut self.data, x, y, direction, present);
+        Self::set_wall_static(&mut self.data, x, y, direction, present);
+        self.loop_detector = LoopDetector::new();
     }
 
     /// Gets the presence of a wall in the specified position and direction
     ///
     /// Arguments:
     /// * `wall_data`: The internal representation of the walls
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
     /// * `direction`: The direction to check
     ///
     /// Return value:
     /// True if the value is present
-    fn get_wall_static(wall_data: &[u8; 199], x: usize, y: usize, direction: Direction) -> bool {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
+    fn get_wall_static(wall_data: &[u8; DATA], x: usize, y: usize, direction: Direction) -> bool {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
 
-        let (wall_index, mask) = World::get_wrapped_wall_index_and_mask(x, y, direction);
+        let (wall_index, mask) = Self::get_wrapped_wall_index_and_mask(x, y, direction);
         let byte = &wall_data[WALL_BLOCK_OFFSET + wall_index];
         return *byte & mask == mask;
     }
@@ -276,8 +495,8 @@ impl World {
     /// Gets the presence of a wall in the specified position and direction
     ///
     /// Arguments:
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
     /// * `direction`: The direction to check
     ///
     /// Return value:
@@ -285,12 +504,48 @@ impl World {
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{World, Direction};
+    /// use simulation::{World, Direction};
     /// let mut world = World::new();
     /// assert!(world.get_wall(0, 0, Direction::Up));
     /// ```
     pub fn get_wall(&self, x: usize, y: usize, direction: Direction) -> bool {
-        World::get_wall_static(&self.data, x, y, direction)
+        Self::get_wall_static(&self.data, x, y, direction)
+    }
+
+    /// Iterates over every wall in the world, row by row. Walls are shared between neighbouring
+    /// squares, so each is listed once as the top or left wall of a square. The walls along the
+    /// bottom and right edges are those along the top and left edges, as the world wraps
+    ///
+    /// Return value:
+    /// The x and y coordinates of the square and the side of it the wall is on
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction};
+    /// let world = World::new();
+    /// assert_eq!(12 + 9, world.get_walls().count());
+    /// assert!(world.get_walls().all(|(_, _, direction)| {
+    ///     direction == Direction::Up || direction == Direction::Left
+    /// }));
+    /// ```
+    pub fn get_walls(&self) -> impl Iterator<Item = (usize, usize, Direction)> + '_ {
+        (0..TILES).flat_map(move |index| {
+            let (x, y) = (index % WIDTH, index / WIDTH);
+            [Direction::Up, Direction::Left]
+                .into_iter()
+                .filter(move |&direction| self.get_wall(x, y, direction))
+                .map(move |direction| (x, y, direction))
+        })
+    }
+
+    /// Gets the width of the world
+    pub fn get_width(&self) -> usize {
+        WIDTH
+    }
+
+    /// Gets the height of the world
+    pub fn get_height(&self) -> usize {
+        HEIGHT
     }
 
     /// Creates a walker. There are a limited number of walkers that can be created, and this
@@ -308,7 +563,7 @@ impl World {
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::{World, Direction, WalkerType};
+    /// use simulation::{World, Direction, WalkerType};
     /// let mut world = World::new();
     /// world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
     /// ```
@@ -319,18 +574,24 @@ impl World {
         direction: Direction,
         walker_type: WalkerType,
     ) -> bool {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
 
         // Check if this tile is already occupied
-        let walker_data = &mut self.data[WALL_BLOCK_OFFSET + WALL_BLOCK_SIZE..];
-        let walker_byte = &mut walker_data[y * WORLD_WIDTH + x];
+        let walker_data = &mut self.data[Self::TILE_BLOCK_OFFSET..];
+        let walker_byte = &mut walker_data[y * WIDTH + x];
 
         if (*walker_byte & ENTITY_TYPE_MASK) != ENTITY_TYPE_EMPTY {
             return false;
         }
 
-        let walker = Walker::new(x as i8, y as i8, direction, walker_type);
+        let walker = Walker::new(
+            Self::take_walker_id(&mut self.next_walker_id),
+            x as i8,
+            y as i8,
+            direction,
+            walker_type,
+        );
         match walker.get_type() {
             WalkerType::Mouse => self.mice.push(walker),
             WalkerType::Cat => self.cats.push(walker),
@@ -350,712 +611,3807 @@ impl World {
                 WalkerType::Mouse => ENTITY_TYPE_MOUSE,
                 WalkerType::Cat => ENTITY_TYPE_CAT,
             };
+        self.loop_detector = LoopDetector::new();
 
         true
     }
 
-    /// Sets the arrow at the specified location
+    /// Gets a walker by its ID. Walkers that have died or been rescued are removed at the end of
+    /// the step, so are no longer found
     ///
     /// Arguments:
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
-    /// * `arrow_type`: The type of arrow to set
-    pub fn set_arrow(&mut self, x: usize, y: usize, tile_type: TileType) {
-        // TODO: Check tile is empty of rockets/holes
-        // TODO: Handle stock of spare arrows
-        World::set_tile_static(&mut self.tiles, x, y, tile_type)
+    /// * `id`: The ID of the walker
+    ///
+    /// Return value:
+    /// The walker, or None if there is no walker with that ID in the world
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_walker(3, 2, Direction::Right, WalkerType::Cat);
+    /// let id = world.get_walkers().next().unwrap().get_id();
+    /// assert_eq!(WalkerType::Cat, world.get_walker(id).unwrap().get_type());
+    /// ```
+    pub fn get_walker(&self, id: WalkerId) -> Option<&Walker> {
+        self.get_walkers().find(|walker| walker.get_id() == id)
     }
 
-    /// Sets the tile at the specified location
+    /// Iterates over the walkers in the world, mice before cats. Walkers of each type are in the
+    /// order they were created, and keep their IDs however the walkers around them are removed
     ///
-    /// Arguments:
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
-    /// * `arrow_type`: The type of arrow to set
-    pub fn set_tile(&mut self, x: usize, y: usize, tile_type: TileType) {
-        World::set_tile_static(&mut self.tiles, x, y, tile_type)
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+    /// world.create_walker(1, 0, Direction::Right, WalkerType::Mouse);
+    /// assert_eq!(2, world.get_walkers().count());
+    /// ```
+    pub fn get_walkers(&self) -> impl Iterator<Item = &Walker> + '_ {
+        self.mice.iter().chain(self.cats.iter())
     }
 
-    /// Sets the tile at the specified location. No checking is performed to
-    /// ensure that the tile is empty - use the set_arrow method for this
-    ///
-    /// Arguments:
-    /// * `tiles`: The internal representation of the tiles
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
-    /// * `tile_type`: The type of tile to set
-    fn set_tile_static(tiles: &mut [TileType; MAX_TILES], x: usize, y: usize, tile_type: TileType) {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
+    /// Gets the mice in the world, in the order they were created
+    pub fn get_mice(&self) -> &[Walker] {
+        &self.mice
+    }
 
-        tiles[y * WORLD_WIDTH + x] = tile_type;
+    /// Gets the cats in the world, in the order they were created
+    pub fn get_cats(&self) -> &[Walker] {
+        &self.cats
     }
 
-    /// Gets the arrow at the specified location
+    /// Counts the walkers of a type that are alive, and that have been rescued or died since the
+    /// world was last reset
     ///
     /// Arguments:
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
-    ///
-    /// Return value:
-    /// The type of arrow present at the specified coordinate
+    /// * `walker_type`: The type of walker to count
     ///
     /// #examples
     /// ```
-    /// use shoko_rocket_rust::World;
+    /// use simulation::{World, Direction, WalkerType};
     /// let mut world = World::new();
-    /// world.get_arrow(0, 0);
+    /// world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+    /// let counts = world.get_walker_counts(WalkerType::Mouse);
+    /// assert_eq!((1, 0, 0), (counts.alive, counts.rescued, counts.dead));
     /// ```
-    pub fn get_arrow(&self, x: usize, y: usize) -> TileType {
-        return World::get_arrow_static(&self.tiles, x, y);
+    pub fn get_walker_counts(&self, walker_type: WalkerType) -> WalkerCounts {
+        let (walkers, removed) = match walker_type {
+            WalkerType::Mouse => (&self.mice, self.removed_mice),
+            WalkerType::Cat => (&self.cats, self.removed_cats),
+        };
+        WalkerCounts {
+            alive: walkers.len(),
+            ..removed
+        }
     }
 
-    /// Gets the arrow at the specified location
+    /// Creates a spawner, which periodically creates walkers while the world runs. The spawner is
+    /// saved in the serialised form
     ///
     /// Arguments:
-    /// * `arrows`: The internal representation of the arrows
-    /// * `x`: The x coordinate to check. Must be in range 0-11
-    /// * `y`: The y coordinate to check. Must be in range 0-8
+    /// * `x`: The x coordinate of the spawner. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate of the spawner. Must be in range 0..HEIGHT
+    /// * `direction`: The direction spawned walkers walk in
+    /// * `walker_type`: The type of walker to spawn
     ///
     /// Return value:
-    /// The type of arrow present at the specified coordinate
-    fn get_arrow_static(arrows: &[TileType; MAX_TILES], x: usize, y: usize) -> TileType {
-        assert!(x < WORLD_WIDTH);
-        assert!(y < WORLD_HEIGHT);
-
-        return arrows[y * WORLD_WIDTH + x];
-    }
-
-    /// Resets the state to that specified in the serialised form
-    pub fn reset() {}
+    /// False if the tile already holds a walker, spawner, rocket or hole
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// assert!(world.create_spawner(0, 0, Direction::Right, WalkerType::Mouse));
+    /// assert!(!world.create_spawner(0, 0, Direction::Left, WalkerType::Cat));
+    /// ```
+    pub fn create_spawner(
+        &mut self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+        walker_type: WalkerType,
+    ) -> bool {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
 
-    /// Advances the simulation state of the world
-    /// * Mice move forward 3 units
-    /// * Cats move forward 2 units
-    /// Each frame check mouse/cat collisions
-    /// * Mice are killed by cats, causing defeat
-    /// On reaching a new grid, walkers check holes/rockets
-    /// * Cats are killed by holes
-    /// * Mice are killed by holes, causing defeat
-    /// * Mice are rescued by rockets
-    /// * Cats destroy rockets, causing defeat
-    /// On reaching a new grid, walkers check arrow
-    /// * Mice are directed by the arrow
-    /// * Cats are directed by arrows, and if turned around, consume the arrow
-    /// On reaching a new grid, walkers check walls
-    /// On all mice rescued, victory
-    pub fn tick(&mut self) -> WorldStateChange {
-        let mut world_state_change = WorldStateChange::NoChange;
+        let spawner_byte = &mut self.data[Self::TILE_BLOCK_OFFSET + y * WIDTH + x];
+        if (*spawner_byte & ENTITY_TYPE_MASK) != ENTITY_TYPE_EMPTY {
+            return false;
+        }
 
-        // 1. Advance mice and cats
-        let all_walkers = self.mice.iter_mut().chain(self.cats.iter_mut());
-        for walker in all_walkers {
-            if walker.walk() == WalkResult::NewSquare {
-                // 2. Check holes, rockets
-                World::check_rockets_and_holes(&mut self.tiles, walker);
+        *spawner_byte = *spawner_byte & (ARROW_PRESENT_MASK | ARROW_DIRECTION_MASK);
+        *spawner_byte = *spawner_byte
+            | match direction {
+                Direction::Up => ENTITY_DIRECTION_UP,
+                Direction::Down => ENTITY_DIRECTION_DOWN,
+                Direction::Left => ENTITY_DIRECTION_LEFT,
+                Direction::Right => ENTITY_DIRECTION_RIGHT,
+            };
+        *spawner_byte = *spawner_byte
+            | match walker_type {
+                WalkerType::Mouse => ENTITY_TYPE_MOUSE_SPAWNER,
+                WalkerType::Cat => ENTITY_TYPE_CAT_SPAWNER,
+            };
 
-                // 3. Check arrows
-                World::check_arrows(&mut self.tiles, walker);
+        self.spawners
+            .push(Spawner::new(x, y, direction, walker_type));
+        self.loop_detector = LoopDetector::new();
+        true
+    }
 
-                // 4. Check walls
-                World::check_walls(&self.data, walker);
+    /// Sets how often a spawner creates walkers. This is not saved in the serialised form
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate of the spawner
+    /// * `y`: The y coordinate of the spawner
+    /// * `interval`: The number of ticks between walkers spawning. Must be at least 1
+    ///
+    /// Return value:
+    /// False if there is no spawner at the location
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_spawner(0, 0, Direction::Right, WalkerType::Mouse);
+    /// assert!(world.set_spawn_interval(0, 0, 120));
+    /// assert!(!world.set_spawn_interval(1, 0, 120));
+    /// ```
+    pub fn set_spawn_interval(&mut self, x: usize, y: usize, interval: u32) -> bool {
+        match self
+            .spawners
+            .iter_mut()
+            .find(|spawner| spawner.get_x() == x && spawner.get_y() == y)
+        {
+            Some(spawner) => {
+                spawner.set_interval(interval);
+                self.loop_detector = LoopDetector::new();
+                true
             }
+            None => false,
         }
+    }
 
-        // 5. Check cat/mouse collisions
+    /// Gets the spawners in the world
+    pub fn get_spawners(&self) -> &[Spawner] {
+        &self.spawners
+    }
 
-        // 6. Check if any mice died to holes, or any cats were rescued
-        if self
-            .mice
-            .iter_mut()
-            .any(|walker| walker.get_state() == WalkerState::Dead)
-            || self
-                .cats
-                .iter_mut()
-                .any(|walker| walker.get_state() == WalkerState::Rescued)
-        {
-            world_state_change = WorldStateChange::Lose;
-        }
+    /// Makes every spawner create walkers of one type at a fixed interval, in place of their own
+    /// type and interval, or returns spawners to normal
+    ///
+    /// Arguments:
+    /// * `flood`: The type of walker and the interval in ticks, or None to stop the flood
+    pub(crate) fn set_spawn_flood(&mut self, flood: Option<(WalkerType, u32)>) {
+        self.spawn_flood =
+            flood.map(|(walker_type, interval)| SpawnFlood::new(walker_type, interval));
+        self.loop_detector = LoopDetector::new();
+    }
 
-        // 7. Check if all mice have been rescued
-        if !self.mice.is_empty()
-            && self
-                .mice
-                .iter()
-                .all(|walker| (*walker).get_state() == WalkerState::Rescued)
-        {
-            world_state_change = WorldStateChange::Win;
-        }
+    /// Sets how far a type of walker moves each step. Walkers already part way across a square
+    /// finish crossing it at the new speed
+    ///
+    /// Arguments:
+    /// * `walker_type`: The type of walker to change
+    /// * `speed`: The distance moved each step. Must be more than zero and less than a square
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, WalkerType, FixedPoint};
+    /// let mut world = World::new();
+    /// world.set_walker_speed(WalkerType::Mouse, FixedPoint::new(0, 12));
+    /// ```
+    pub fn set_walker_speed(&mut self, walker_type: WalkerType, speed: FixedPoint) {
+        assert!(speed.scaled() > 0 && speed.scaled() < UNITS_PER_SQUARE);
 
-        // 8. Remove dead/rescued walkers
-        self.mice
-            .retain(|walker| walker.get_state() == WalkerState::Alive);
-        self.cats
-            .retain(|walker| walker.get_state() == WalkerState::Alive);
+        match walker_type {
+            WalkerType::Mouse => self.ruleset.mouse_speed = speed,
+            WalkerType::Cat => self.ruleset.cat_speed = speed,
+        }
+        self.loop_detector = LoopDetector::new();
+    }
 
-        // 9. Return the new world state for the user to handle
-        world_state_change
+    /// Gets how far a type of walker moves each step
+    pub fn get_walker_speed(&self, walker_type: WalkerType) -> FixedPoint {
+        self.ruleset.get_speed(walker_type)
     }
 
-    /// Handles collisions with walls
-    /// * If not blocked, keep going straight
-    /// * If blocked and able to turn right, turn right
-    /// * If blocked and unable to turn right, turn left
-    /// * If blocked and unable to turn left or right, return around
-    /// * If blocked all around, keep going straight. This will in practice not happen due to
-    ///   level design
+    /// Sets the rules the world is simulated with. Worlds start with the puzzle rules
     ///
     /// Arguments:
-    /// * `wall_data`: The internal representation of the walls
-    /// * `walker`: The Walker to check
-    fn check_walls(wall_data: &[u8; 199], walker: &mut Walker) {
-        let (x, y) =
-            (walker.get_x().integer_part() as usize, walker.get_y().integer_part() as usize);
-        let direction = walker.get_direction();
+    /// * `ruleset`: The rules to simulate with. Both speeds must be more than zero and less than
+    ///   a square
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Ruleset, Turn};
+    /// let mut world = World::new();
+    /// world.set_ruleset(Ruleset {
+    ///     turn_priority: [Turn::Left, Turn::Right, Turn::Back],
+    ///     ..Ruleset::puzzle()
+    /// });
+    /// ```
+    pub fn set_ruleset(&mut self, ruleset: Ruleset) {
+        for speed in [ruleset.mouse_speed, ruleset.cat_speed] {
+            assert!(speed.scaled() > 0 && speed.scaled() < UNITS_PER_SQUARE);
+        }
 
-        // Priority list of directions to travel. The first clear direction will be used
-        let candidate_directions = [
-            direction,
-            direction.turn_right(),
-            direction.turn_left(),
-            direction.turn_around(),
-        ];
+        self.ruleset = ruleset;
+        self.loop_detector = LoopDetector::new();
+    }
 
-        for candidate_direction in candidate_directions {
-            if !World::get_wall_static(wall_data, x, y, candidate_direction) {
-                walker.set_direction(candidate_direction);
-                break;
-            }
-        }
+    /// Gets the rules the world is simulated with
+    pub fn get_ruleset(&self) -> &Ruleset {
+        &self.ruleset
     }
 
-    /// Handles collisions between arrows and walkers
-    /// * Walkers are turned to face the arrow direction
-    /// * If a cat is turned 180 degrees then the arrow is diminished
+    /// Sets the arrow at the specified location. The stock of spare arrows is not used, so this is
+    /// intended for building levels. Use place_arrow for arrows placed by the player
     ///
     /// Arguments:
-    /// * `tile`: The tiles
-    /// * `walker`: The Walker to check
-    fn check_arrows(tiles: &mut [TileType; MAX_TILES], walker: &mut Walker) {
-        let (x, y) =
-            (walker.get_x().integer_part() as usize, walker.get_y().integer_part() as usize);
-        let arrow = World::get_arrow_static(tiles, x, y);
-        let arrow_direction = arrow.try_into();
-        match arrow_direction {
-            Ok(direction) => {
-                // If turning around a cat, diminish the arrow
-                if walker.get_type() == WalkerType::Cat
-                    && walker.get_direction().turn_around() == direction
-                {
-                    World::set_tile_static(tiles, x, y, arrow.diminish());
-                }
-                walker.set_direction(direction);
-            }
-            Err(_) => {
-                // Indicates an empty/hole/rocket tile, which is fine
-            }
-        };
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    /// * `arrow_type`: The type of arrow to set
+    pub fn set_arrow(&mut self, x: usize, y: usize, tile_type: TileType) {
+        self.set_tile(x, y, tile_type);
     }
 
-    /// Handles collisions between holes/rockets and walkers
-    /// * Holes kill everything
-    /// * Rockets rescue mice
-    /// * Cats kill rockets
-    /// * Mouse death changes world state to a loss
-    /// * Rocket death changes world state to a loss
-    /// * Rescue of all mice changes world state to a win
+    /// Places an arrow from the stock of spare arrows. If an arrow of another direction is
+    /// already present it is returned to the stock
     ///
     /// Arguments:
-    /// * `tiles`: The tiles
-    /// * `walker`: The Walker to check
-    fn check_rockets_and_holes(tiles: &mut [TileType; MAX_TILES], walker: &mut Walker) {
-        let (x, y) =
-            (walker.get_x().integer_part() as usize, walker.get_y().integer_part() as usize);
-        let tile = tiles[y * WORLD_WIDTH + x];
-
-        match tile {
-            TileType::Hole => walker.kill(),
-            TileType::Rocket => walker.rescue(),
-            _ => {}
+    /// * `x`: The x coordinate to place at. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to place at. Must be in range 0..HEIGHT
+    /// * `direction`: The direction of the arrow
+    ///
+    /// Return value:
+    /// An error if the world is not stopped, the tile is a rocket/hole, or no arrows of that
+    /// direction are in stock
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, PlacementError};
+    /// let mut world = World::new();
+    /// assert_eq!(Err(PlacementError::OutOfStock), world.place_arrow(0, 0, Direction::Up));
+    /// ```
+    pub fn place_arrow(
+        &mut self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> Result<(), PlacementError> {
+        if self.state != WorldState::Stopped {
+            return Err(PlacementError::NotStopped);
         }
-    }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        let existing = Self::get_arrow_static(&self.placed_tiles, x, y);
+        let existing_direction: Result<Direction, ()> = existing.try_into();
+        if matches!(existing, TileType::Rocket | TileType::RocketDestroyed | TileType::Hole) {
+            return Err(PlacementError::TileOccupied);
+        }
 
-    /// GIVEN A row of cells and a direction
-    /// WHEN we calculate the wall index and bitmask
-    /// THEN the correct values are returned
-    #[test]
-    fn index_and_mask() {
-        assert_eq!((0, 0b00000001), World::get_wrapped_wall_index_and_mask(0, 0, Direction::Up));
-        assert_eq!((0, 0b00000100), World::get_wrapped_wall_index_and_mask(1, 0, Direction::Up));
-        assert_eq!((0, 0b00010000), World::get_wrapped_wall_index_and_mask(2, 0, Direction::Up));
-        assert_eq!((0, 0b01000000), World::get_wrapped_wall_index_and_mask(3, 0, Direction::Up));
-        assert_eq!((1, 0b00000001), World::get_wrapped_wall_index_and_mask(4, 0, Direction::Up));
-
-        assert_eq!((0, 0b00000010), World::get_wrapped_wall_index_and_mask(0, 0, Direction::Left));
-        assert_eq!((0, 0b00001000), World::get_wrapped_wall_index_and_mask(1, 0, Direction::Left));
-        assert_eq!((0, 0b00100000), World::get_wrapped_wall_index_and_mask(2, 0, Direction::Left));
-        assert_eq!((0, 0b10000000), World::get_wrapped_wall_index_and_mask(3, 0, Direction::Left));
-        assert_eq!((1, 0b00000010), World::get_wrapped_wall_index_and_mask(4, 0, Direction::Left));
+        // Placing over an arrow of the same direction leaves everything unchanged
+        if existing_direction == Ok(direction) {
+            return Ok(());
+        }
 
-        // Down walls are the top wall of the cell below, increasing the index by 3
-        assert_eq!((3, 0b00000001), World::get_wrapped_wall_index_and_mask(0, 0, Direction::Down));
-        assert_eq!((3, 0b00000100), World::get_wrapped_wall_index_and_mask(1, 0, Direction::Down));
-        assert_eq!((3, 0b00010000), World::get_wrapped_wall_index_and_mask(2, 0, Direction::Down));
-        assert_eq!((3, 0b01000000), World::get_wrapped_wall_index_and_mask(3, 0, Direction::Down));
-        assert_eq!((4, 0b00000001), World::get_wrapped_wall_index_and_mask(4, 0, Direction::Down));
+        if self.arrow_stock[direction] == 0 {
+            return Err(PlacementError::OutOfStock);
+        }
 
-        // Right walls are the left wall of the cell to the left, shifting the mask and increasing
-        // the index by 1 for every 4th element
-        assert_eq!((0, 0b00001000), World::get_wrapped_wall_index_and_mask(0, 0, Direction::Right));
-        assert_eq!((0, 0b00100000), World::get_wrapped_wall_index_and_mask(1, 0, Direction::Right));
-        assert_eq!((0, 0b10000000), World::get_wrapped_wall_index_and_mask(2, 0, Direction::Right));
-        assert_eq!((1, 0b00000010), World::get_wrapped_wall_index_and_mask(3, 0, Direction::Right));
-        assert_eq!((1, 0b00001000), World::get_wrapped_wall_index_and_mask(4, 0, Direction::Right));
+        // Swap any existing arrow back into the stock
+        if let Ok(existing_direction) = existing_direction {
+            self.arrow_stock[existing_direction] += 1;
+        }
+        self.arrow_stock[direction] -= 1;
+        self.set_placed_tile(x, y, direction.into());
+        Ok(())
     }
 
-    /// GIVEN a newly created world
-    /// WHEN we check the walls
-    /// THEN the walls are around the edge only
-    #[test]
+    /// Removes an arrow, returning it to the stock of spare arrows
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to remove from. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to remove from. Must be in range 0..HEIGHT
+    ///
+    /// Return value:
+    /// The direction of the removed arrow, or an error if the world is not stopped or there was
+    /// no arrow
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, PlacementError};
+    /// let mut world = World::new();
+    /// assert_eq!(Err(PlacementError::NoArrow), world.remove_arrow(0, 0));
+    /// ```
+    pub fn remove_arrow(&mut self, x: usize, y: usize) -> Result<Direction, PlacementError> {
+        if self.state != WorldState::Stopped {
+            return Err(PlacementError::NotStopped);
+        }
+
+        let existing = Self::get_arrow_static(&self.placed_tiles, x, y);
+        let direction: Direction = existing.try_into().map_err(|_| PlacementError::NoArrow)?;
+
+        self.arrow_stock[direction] += 1;
+        self.set_placed_tile(x, y, TileType::Empty);
+        Ok(direction)
+    }
+
+    /// Places an arrow for a player during a battle. The arrow is removed after a number of
+    /// steps, and if the player already has the most arrows allowed on the board their oldest is
+    /// removed to make room. These arrows are not part of the puzzle, so are removed by reset and
+    /// are not saved in the serialised form
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to place at
+    /// * `y`: The y coordinate to place at
+    /// * `direction`: The direction of the arrow
+    /// * `owner`: The player placing the arrow
+    /// * `lifetime`: The number of steps before the arrow is removed. Must be at least 1
+    /// * `max_arrows`: The most arrows the player may have on the board. Must be at least 1
+    ///
+    /// Return value:
+    /// An error if the tile is a rocket/hole, or holds an arrow that the player does not own
+    pub(crate) fn place_owned_arrow(
+        &mut self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+        owner: Player,
+        lifetime: u32,
+        max_arrows: usize,
+    ) -> Result<(), PlacementError> {
+        assert!(lifetime > 0);
+        assert!(max_arrows > 0);
+
+        let index = y * WIDTH + x;
+        let existing = Self::get_arrow_static(&self.tiles, x, y);
+        match (existing, self.arrow_owners[index]) {
+            (TileType::Rocket | TileType::RocketDestroyed | TileType::Hole, _) => {
+                return Err(PlacementError::TileOccupied);
+            }
+            (_, Some(arrow)) if arrow.owner != owner => {
+                return Err(PlacementError::TileOccupied);
+            }
+            (TileType::Empty, _) | (_, Some(_)) => {}
+            // Arrows that are part of the puzzle belong to nobody
+            (_, None) => return Err(PlacementError::TileOccupied),
+        }
+
+        // Replacing one of the player's own arrows does not need room making
+        if self.arrow_owners[index].is_none() {
+            let placed = self
+                .arrow_owners
+                .iter()
+                .flatten()
+                .filter(|arrow| arrow.owner == owner)
+                .count();
+            if placed >= max_arrows {
+                let oldest = (0..TILES)
+                    .filter_map(|i| self.arrow_owners[i].map(|arrow| (i, arrow)))
+                    .filter(|(_, arrow)| arrow.owner == owner)
+                    .min_by_key(|(_, arrow)| arrow.sequence)
+                    .map(|(i, _)| i);
+                if let Some(oldest) = oldest {
+                    self.tiles[oldest] = TileType::Empty;
+                    self.arrow_owners[oldest] = None;
+                }
+            }
+        }
+
+        self.tiles[index] = direction.into();
+        self.arrow_owners[index] = Some(OwnedArrow {
+            owner,
+            ticks_remaining: lifetime,
+            sequence: self.arrow_sequence,
+        });
+        self.arrow_sequence = self.arrow_sequence.wrapping_add(1);
+        self.loop_detector = LoopDetector::new();
+        Ok(())
+    }
+
+    /// Gets the player that placed the arrow at the specified location, if it was placed during
+    /// a battle
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check
+    /// * `y`: The y coordinate to check
+    pub fn get_arrow_owner(&self, x: usize, y: usize) -> Option<Player> {
+        self.arrow_owners[y * WIDTH + x].map(|arrow| arrow.owner)
+    }
+
+    /// Gets the stock of arrows available to place
+    pub fn get_arrow_stock(&self) -> ArrowStock {
+        self.arrow_stock
+    }
+
+    /// Places the solution arrows stored in the serialised form, taking them from the stock
+    ///
+    /// Return value:
+    /// An error if any of the solution arrows could not be placed
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::World;
+    /// let mut world = World::new();
+    /// assert_eq!(Ok(()), world.place_solution());
+    /// ```
+    pub fn place_solution(&mut self) -> Result<(), PlacementError> {
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let tile_byte = self.data[Self::TILE_BLOCK_OFFSET + y * WIDTH + x];
+                if let Some(direction) = Self::read_arrow(tile_byte) {
+                    self.place_arrow(x, y, direction)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the tile at the specified location. The serialised form is updated to match, so
+    /// rockets and holes are saved, and arrows are saved as part of the solution. Rockets and
+    /// holes share the serialised square with walkers and spawners, so cannot be placed where a
+    /// walker starts or a spawner is
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    /// * `arrow_type`: The type of arrow to set
+    ///
+    /// Return value:
+    /// True if the tile was set, false if a rocket or hole was refused
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, TileType, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+    /// assert_eq!(false, world.set_tile(0, 0, TileType::Rocket));
+    /// assert_eq!(true, world.set_tile(1, 0, TileType::Rocket));
+    /// ```
+    pub fn set_tile(&mut self, x: usize, y: usize, tile_type: TileType) -> bool {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
+
+        let entity_type = self.data[Self::TILE_BLOCK_OFFSET + y * WIDTH + x] & ENTITY_TYPE_MASK;
+        let covers_entity = matches!(
+            entity_type,
+            ENTITY_TYPE_MOUSE
+                | ENTITY_TYPE_CAT
+                | ENTITY_TYPE_MOUSE_SPAWNER
+                | ENTITY_TYPE_CAT_SPAWNER
+        );
+        let is_entity =
+            matches!(tile_type, TileType::Rocket | TileType::RocketDestroyed | TileType::Hole);
+        if covers_entity && is_entity {
+            return false;
+        }
+
+        self.set_placed_tile(x, y, tile_type);
+        Self::write_tile(&mut self.data, x, y, tile_type);
+        true
+    }
+
+    /// Sets the tile at the specified location, without changing the serialised form. Used for
+    /// arrows placed by the player, which are not part of the puzzle
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    /// * `arrow_type`: The type of arrow to set
+    fn set_placed_tile(&mut self, x: usize, y: usize, tile_type: TileType) {
+        Self::set_tile_static(&mut self.tiles, x, y, tile_type);
+        Self::set_tile_static(&mut self.placed_tiles, x, y, tile_type);
+        self.loop_detector = LoopDetector::new();
+    }
+
+    /// Writes a tile to the tile block of the serialised form. Walkers starting on the tile are
+    /// kept unless a rocket or hole replaces them
+    ///
+    /// Arguments:
+    /// * `data`: The serialised form
+    /// * `x`: The x coordinate to write. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to write. Must be in range 0..HEIGHT
+    /// * `tile_type`: The type of tile to write
+    fn write_tile(data: &mut [u8; DATA], x: usize, y: usize, tile_type: TileType) {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
+
+        let tile_byte = &mut data[Self::TILE_BLOCK_OFFSET + y * WIDTH + x];
+        if matches!(*tile_byte & ENTITY_TYPE_MASK, ENTITY_TYPE_ROCKET | ENTITY_TYPE_HOLE) {
+            *tile_byte &= !(ENTITY_TYPE_MASK | ENTITY_DIRECTION_MASK);
+        }
+
+        let direction: Result<Direction, ()> = tile_type.try_into();
+        *tile_byte = match (tile_type, direction) {
+            // Destroyed rockets are only a simulation state, so are saved intact
+            (TileType::Rocket | TileType::RocketDestroyed, _) => ENTITY_TYPE_ROCKET,
+            (TileType::Hole, _) => ENTITY_TYPE_HOLE,
+            (_, Ok(direction)) => {
+                (*tile_byte & !ARROW_DIRECTION_MASK)
+                    | ARROW_PRESENT_MASK
+                    | match direction {
+                        Direction::Up => ARROW_DIRECTION_UP,
+                        Direction::Down => ARROW_DIRECTION_DOWN,
+                        Direction::Left => ARROW_DIRECTION_LEFT,
+                        Direction::Right => ARROW_DIRECTION_RIGHT,
+                    }
+            }
+            (_, Err(())) => *tile_byte & !(ARROW_PRESENT_MASK | ARROW_DIRECTION_MASK),
+        };
+    }
+
+    /// Serialises the world to the same form read by load and output by the puzzle! macro.
+    /// Walls, walkers, rockets and holes are saved, along with arrows set by set_arrow or
+    /// set_tile, which form the solution. Arrows placed from the stock by the player are not saved
+    ///
+    /// Return value:
+    /// The serialised world
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::World;
+    /// let world = World::new();
+    /// let reloaded = World::load(world.to_bytes());
+    /// assert_eq!(world.to_bytes(), reloaded.to_bytes());
+    /// ```
+    pub fn to_bytes(&self) -> [u8; DATA] {
+        self.data
+    }
+
+    /// Captures the complete live state of the world, including walkers part way between
+    /// squares, diminished arrows, the remaining stock and the world state. Unlike to_bytes
+    /// this is intended to be restored into an identical simulation
+    ///
+    /// Arguments:
+    /// * `buffer`: The buffer to write to. SNAPSHOT_MAX_SIZE bytes is always sufficient
+    ///
+    /// Return value:
+    /// The number of bytes written, or an error if the buffer is too small
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, SNAPSHOT_MAX_SIZE};
+    /// let world = World::new();
+    /// let mut buffer = [0; SNAPSHOT_MAX_SIZE];
+    /// let length = world.snapshot(&mut buffer).unwrap();
+    /// let restored = <World>::restore(&buffer[..length]).unwrap();
+    /// assert_eq!(world.to_bytes(), restored.to_bytes());
+    /// ```
+    pub fn snapshot(&self, buffer: &mut [u8]) -> Result<usize, SnapshotError> {
+        let mut writer = SnapshotWriter::new(buffer)?;
+        writer.write_u8(WIDTH as u8)?;
+        writer.write_u8(HEIGHT as u8)?;
+        writer.write_bytes(&self.data)?;
+        writer.write_world_state(self.state)?;
+        writer.write_ruleset(&self.ruleset)?;
+        for direction in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            writer.write_u8(self.arrow_stock[direction])?;
+        }
+        for tile in self.tiles.iter().chain(self.placed_tiles.iter()) {
+            writer.write_tile(*tile)?;
+        }
+        for walkers in [&self.mice, &self.cats] {
+            writer.write_u16(walkers.len() as u16)?;
+            for walker in walkers.iter() {
+                writer.write_walker(walker)?;
+            }
+        }
+        writer.write_u16(self.rocket_events.len() as u16)?;
+        for rocket_event in self.rocket_events.iter() {
+            writer.write_u8(rocket_event.x as u8)?;
+            writer.write_u8(rocket_event.y as u8)?;
+            writer.write_rocket_event_kind(rocket_event.kind)?;
+        }
+        writer.write_u16(self.spawners.len() as u16)?;
+        for spawner in self.spawners.iter() {
+            writer.write_spawner(spawner)?;
+        }
+        writer.write_u32(self.arrow_sequence)?;
+        writer.write_u16(self.arrow_owners.iter().flatten().count() as u16)?;
+        for (index, owned_arrow) in self.arrow_owners.iter().enumerate() {
+            if let Some(arrow) = owned_arrow {
+                writer.write_u8((index % WIDTH) as u8)?;
+                writer.write_u8((index / WIDTH) as u8)?;
+                writer.write_player(arrow.owner)?;
+                writer.write_u32(arrow.ticks_remaining)?;
+                writer.write_u32(arrow.sequence)?;
+            }
+        }
+        writer.write_u32(self.seed)?;
+        writer.write_u32(self.rng.get_state())?;
+        match self.spawn_flood {
+            Some(flood) => {
+                writer.write_u8(1)?;
+                writer.write_walker_type(flood.walker_type)?;
+                writer.write_u32(flood.interval)?;
+                writer.write_u32(flood.countdown)?;
+            }
+            None => writer.write_u8(0)?,
+        }
+        writer.write_u32(self.next_walker_id.get_value())?;
+        for removed in [self.removed_mice, self.removed_cats] {
+            writer.write_u32(removed.rescued as u32)?;
+            writer.write_u32(removed.dead as u32)?;
+        }
+        match self.loop_detector.saved {
+            Some(hash) => {
+                writer.write_u8(1)?;
+                writer.write_u64(hash)?;
+            }
+            None => writer.write_u8(0)?,
+        }
+        writer.write_u32(self.loop_detector.power)?;
+        writer.write_u32(self.loop_detector.length)?;
+
+        Ok(writer.finish())
+    }
+
+    /// Restores a world captured by snapshot
+    ///
+    /// Arguments:
+    /// * `snapshot`: The snapshot, as written by snapshot
+    ///
+    /// Return value:
+    /// The restored world, or an error if the snapshot is malformed, of an unsupported version or
+    /// of a different size of world
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, SnapshotError};
+    /// assert_eq!(Err(SnapshotError::BadMagic), <World>::restore(&[0; 8]).map(|_| ()));
+    /// ```
+    pub fn restore(snapshot: &[u8]) -> Result<Self, SnapshotError> {
+        let mut reader = SnapshotReader::new(snapshot)?;
+        if reader.read_u8()? as usize != WIDTH || reader.read_u8()? as usize != HEIGHT {
+            return Err(SnapshotError::WrongSize);
+        }
+
+        let mut world = Self::new_sized();
+        world.data.copy_from_slice(reader.read_bytes(DATA)?);
+        world.state = reader.read_world_state()?;
+        world.ruleset = reader.read_ruleset()?;
+        for direction in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            world.arrow_stock[direction] = reader.read_u8()?;
+        }
+        for tile in world.tiles.iter_mut() {
+            *tile = reader.read_tile()?;
+        }
+        for tile in world.placed_tiles.iter_mut() {
+            *tile = reader.read_tile()?;
+        }
+        for walker_type in [WalkerType::Mouse, WalkerType::Cat] {
+            let count = reader.read_u16()? as usize;
+            if count > TILES {
+                return Err(SnapshotError::Corrupt);
+            }
+            for _ in 0..count {
+                let walker = reader.read_walker()?;
+                // Only live walkers on the board are kept between ticks
+                let on_board = |position: FixedPoint, size: usize| {
+                    (0..size as i32).contains(&(position.floor() as i32))
+                };
+                if walker.get_type() != walker_type
+                    || walker.get_state() != WalkerState::Alive
+                    || !on_board(walker.get_x(), WIDTH)
+                    || !on_board(walker.get_y(), HEIGHT)
+                {
+                    return Err(SnapshotError::Corrupt);
+                }
+                match walker_type {
+                    WalkerType::Mouse => world.mice.push(walker),
+                    WalkerType::Cat => world.cats.push(walker),
+                }
+            }
+        }
+        let count = reader.read_u16()? as usize;
+        if count > TILES {
+            return Err(SnapshotError::Corrupt);
+        }
+        for _ in 0..count {
+            let x = reader.read_u8()? as usize;
+            let y = reader.read_u8()? as usize;
+            let kind = reader.read_rocket_event_kind()?;
+            world.rocket_events.push(RocketEvent { x, y, kind });
+        }
+        let count = reader.read_u16()? as usize;
+        if count > TILES {
+            return Err(SnapshotError::Corrupt);
+        }
+        for _ in 0..count {
+            let spawner = reader.read_spawner()?;
+            if spawner.get_x() >= WIDTH || spawner.get_y() >= HEIGHT {
+                return Err(SnapshotError::Corrupt);
+            }
+            world.spawners.push(spawner);
+        }
+        world.arrow_sequence = reader.read_u32()?;
+        let count = reader.read_u16()? as usize;
+        if count > TILES {
+            return Err(SnapshotError::Corrupt);
+        }
+        for _ in 0..count {
+            let x = reader.read_u8()? as usize;
+            let y = reader.read_u8()? as usize;
+            let owner = reader.read_player()?;
+            let ticks_remaining = reader.read_u32()?;
+            let sequence = reader.read_u32()?;
+            if x >= WIDTH || y >= HEIGHT || ticks_remaining == 0 {
+                return Err(SnapshotError::Corrupt);
+            }
+            world.arrow_owners[y * WIDTH + x] = Some(OwnedArrow {
+                owner,
+                ticks_remaining,
+                sequence,
+            });
+        }
+        world.seed = reader.read_u32()?;
+        world.rng = Rng::restore(reader.read_u32()?);
+        world.spawn_flood = match reader.read_u8()? {
+            0 => None,
+            1 => {
+                let walker_type = reader.read_walker_type()?;
+                let interval = reader.read_u32()?;
+                let countdown = reader.read_u32()?;
+                if interval == 0 || countdown == 0 || countdown > interval {
+                    return Err(SnapshotError::Corrupt);
+                }
+                Some(SpawnFlood {
+                    walker_type,
+                    interval,
+                    countdown,
+                })
+            }
+            _ => return Err(SnapshotError::Corrupt),
+        };
+        world.next_walker_id = WalkerId::new(reader.read_u32()?);
+        for removed in [&mut world.removed_mice, &mut world.removed_cats] {
+            removed.rescued = reader.read_u32()? as usize;
+            removed.dead = reader.read_u32()? as usize;
+        }
+        world.loop_detector.saved = match reader.read_u8()? {
+            0 => None,
+            1 => Some(reader.read_u64()?),
+            _ => return Err(SnapshotError::Corrupt),
+        };
+        world.loop_detector.power = reader.read_u32()?;
+        world.loop_detector.length = reader.read_u32()?;
+        if world.loop_detector.power == 0 {
+            return Err(SnapshotError::Corrupt);
+        }
+
+        reader.finish()?;
+        Ok(world)
+    }
+
+    /// Sets the tile at the specified location. No checking is performed to
+    /// ensure that the tile is empty - use the set_arrow method for this
+    ///
+    /// Arguments:
+    /// * `tiles`: The internal representation of the tiles
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    /// * `tile_type`: The type of tile to set
+    fn set_tile_static(tiles: &mut [TileType; TILES], x: usize, y: usize, tile_type: TileType) {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
+
+        tiles[y * WIDTH + x] = tile_type;
+    }
+
+    /// Gets the arrow at the specified location
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    ///
+    /// Return value:
+    /// The type of arrow present at the specified coordinate
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::World;
+    /// let mut world = World::new();
+    /// world.get_arrow(0, 0);
+    /// ```
+    pub fn get_arrow(&self, x: usize, y: usize) -> TileType {
+        return Self::get_arrow_static(&self.tiles, x, y);
+    }
+
+    /// Gets the tile at the specified location, which may be an arrow, rocket or hole
+    ///
+    /// Arguments:
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    ///
+    /// Return value:
+    /// The type of tile present at the specified coordinate
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, TileType};
+    /// let mut world = World::new();
+    /// world.set_tile(2, 3, TileType::Hole);
+    /// assert_eq!(TileType::Hole, world.get_tile(2, 3));
+    /// ```
+    pub fn get_tile(&self, x: usize, y: usize) -> TileType {
+        Self::get_arrow_static(&self.tiles, x, y)
+    }
+
+    /// Iterates over every tile in the world, row by row
+    ///
+    /// Return value:
+    /// The x and y coordinates and type of each tile
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, TileType};
+    /// let mut world = World::new();
+    /// world.set_tile(2, 3, TileType::Rocket);
+    /// assert_eq!(
+    ///     Some((2, 3, TileType::Rocket)),
+    ///     world.get_tiles().find(|&(_, _, tile)| tile != TileType::Empty)
+    /// );
+    /// ```
+    pub fn get_tiles(&self) -> impl Iterator<Item = (usize, usize, TileType)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(index, &tile)| (index % WIDTH, index / WIDTH, tile))
+    }
+
+    /// Gets the arrow at the specified location
+    ///
+    /// Arguments:
+    /// * `arrows`: The internal representation of the arrows
+    /// * `x`: The x coordinate to check. Must be in range 0..WIDTH
+    /// * `y`: The y coordinate to check. Must be in range 0..HEIGHT
+    ///
+    /// Return value:
+    /// The type of arrow present at the specified coordinate
+    fn get_arrow_static(arrows: &[TileType; TILES], x: usize, y: usize) -> TileType {
+        assert!(x < WIDTH);
+        assert!(y < HEIGHT);
+
+        return arrows[y * WIDTH + x];
+    }
+
+    /// Sets what happens to rockets entered by cats. Puzzles destroy the rocket, while battles
+    /// only mark it as hit
+    ///
+    /// Arguments:
+    /// * `rocket_penalty`: The penalty to apply
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, RocketPenalty};
+    /// let mut world = World::new();
+    /// world.set_rocket_penalty(RocketPenalty::Hit);
+    /// ```
+    pub fn set_rocket_penalty(&mut self, rocket_penalty: RocketPenalty) {
+        self.ruleset.rocket_penalty = rocket_penalty;
+        self.loop_detector = LoopDetector::new();
+    }
+
+    /// Gets what happens to rockets entered by cats
+    pub fn get_rocket_penalty(&self) -> RocketPenalty {
+        self.ruleset.rocket_penalty
+    }
+
+    /// Gets the walkers that entered rockets during the last tick. Fast forward runs several
+    /// steps in a tick, so only the first TILES are kept. Every walker entering a rocket is
+    /// reported to the event sink of tick_with_events
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::World;
+    /// let mut world = World::new();
+    /// world.tick();
+    /// assert!(world.get_rocket_events().is_empty());
+    /// ```
+    pub fn get_rocket_events(&self) -> &[RocketEvent] {
+        &self.rocket_events
+    }
+
+    /// Resets the state to that specified in the serialised form, so that a failed attempt can
+    /// be retried
+    /// * Walkers are removed, then respawned from the serialised form
+    /// * Placed arrows are kept, and any diminished or removed by cats are restored
+    /// * Destroyed rockets are restored
+    ///
+    /// Respawned walkers are given new IDs, so they are never confused with those removed
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+    /// world.tick();
+    /// world.reset();
+    /// ```
+    pub fn reset(&mut self) {
+        self.mice.clear();
+        self.cats.clear();
+        self.rocket_events.clear();
+        self.tiles = self.placed_tiles;
+        self.arrow_owners = [None; TILES];
+        self.rng = Rng::new(self.seed);
+        self.removed_mice = WalkerCounts::default();
+        self.removed_cats = WalkerCounts::default();
+        self.loop_detector = LoopDetector::new();
+        for spawner in self.spawners.iter_mut() {
+            spawner.reset();
+        }
+
+        let tile_block = &self.data[Self::TILE_BLOCK_OFFSET..Self::TILE_BLOCK_OFFSET + TILES];
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let tile_byte = tile_block[y * WIDTH + x];
+                let walker_type = match tile_byte & ENTITY_TYPE_MASK {
+                    ENTITY_TYPE_MOUSE => WalkerType::Mouse,
+                    ENTITY_TYPE_CAT => WalkerType::Cat,
+                    _ => continue,
+                };
+
+                let walker = Walker::new(
+                    Self::take_walker_id(&mut self.next_walker_id),
+                    x as i8,
+                    y as i8,
+                    Self::read_entity_direction(tile_byte),
+                    walker_type,
+                );
+                match walker_type {
+                    WalkerType::Mouse => self.mice.push(walker),
+                    WalkerType::Cat => self.cats.push(walker),
+                }
+            }
+        }
+    }
+
+    /// Seeds the random number generator. Worlds with the same seed and inputs behave identically.
+    /// The generator restarts from this seed whenever the world is reset
+    ///
+    /// Arguments:
+    /// * `seed`: The seed. All values are valid
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::World;
+    /// let mut world = World::new();
+    /// world.set_seed(1234);
+    /// assert_eq!(1234, world.get_seed());
+    /// ```
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+    }
+
+    /// Gets the seed of the random number generator
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Gets the random number generator. All randomness in the simulation must come from here so
+    /// that it is captured by snapshots and replays
+    pub fn get_rng_mut(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// Gets the state of the world
+    pub fn get_state(&self) -> WorldState {
+        self.state
+    }
+
+    /// Gets the squares currently occupied by living walkers
+    pub(crate) fn walker_squares(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.mice
+            .iter()
+            .chain(self.cats.iter())
+            .map(Self::walker_square)
+    }
+
+    /// Starts the simulation running at normal speed. Has no effect once the puzzle has been
+    /// won or lost
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, WorldState};
+    /// let mut world = World::new();
+    /// world.start();
+    /// assert_eq!(WorldState::Running, world.get_state());
+    /// ```
+    pub fn start(&mut self) {
+        if let WorldState::Stopped | WorldState::RunningFast = self.state {
+            self.state = WorldState::Running;
+        }
+    }
+
+    /// Starts the simulation running fast, with several simulation steps per tick. Has no effect
+    /// once the puzzle has been won or lost
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, WorldState};
+    /// let mut world = World::new();
+    /// world.fast_forward();
+    /// assert_eq!(WorldState::RunningFast, world.get_state());
+    /// ```
+    pub fn fast_forward(&mut self) {
+        if let WorldState::Stopped | WorldState::Running = self.state {
+            self.state = WorldState::RunningFast;
+        }
+    }
+
+    /// Stops the simulation and resets the world so that arrows can be edited and the puzzle
+    /// attempted again
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, WorldState};
+    /// let mut world = World::new();
+    /// world.start();
+    /// world.stop();
+    /// assert_eq!(WorldState::Stopped, world.get_state());
+    /// ```
+    pub fn stop(&mut self) {
+        if self.state != WorldState::Stopped {
+            self.state = WorldState::Stopped;
+            self.reset();
+        }
+    }
+
+    /// Advances the world according to its state
+    /// * Stopped worlds do not advance
+    /// * Running worlds advance a single simulation step
+    /// * Fast running worlds advance several simulation steps
+    /// * Won or lost worlds do not advance
+    ///
+    /// Winning or losing latches the state to Success or Defeat until stopped
+    ///
+    /// Return value:
+    /// The change in world state caused by the tick
+    pub fn tick(&mut self) -> WorldStateChange {
+        self.tick_with_events(&mut ())
+    }
+
+    /// Advances the world according to its state, as tick does, reporting everything that
+    /// happens to a sink so that it can be animated or played as a sound
+    ///
+    /// Arguments:
+    /// * `events`: Receives the events of every step run
+    ///
+    /// Return value:
+    /// The change in world state caused by the tick
+    ///
+    /// #examples
+    /// ```
+    /// use simulation::{World, WorldEvent, Direction, WalkerType};
+    /// let mut world = World::new();
+    /// world.create_walker(0, 0, Direction::Up, WalkerType::Mouse);
+    /// world.start();
+    /// let mut bounces = 0;
+    /// for _ in 0..60 {
+    ///     world.tick_with_events(&mut |event| {
+    ///         if let WorldEvent::WallBounce { .. } = event {
+    ///             bounces += 1;
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn tick_with_events<S: EventSink>(&mut self, events: &mut S) -> WorldStateChange {
+        self.rocket_events.clear();
+
+        let steps = match self.state {
+            WorldState::Running => 1,
+            WorldState::RunningFast => FAST_FORWARD_STEPS,
+            WorldState::Stopped | WorldState::Success | WorldState::Defeat => 0,
+        };
+
+        for _ in 0..steps {
+            let world_state_change = self.step(events);
+            match world_state_change {
+                WorldStateChange::Win => {
+                    self.state = WorldState::Success;
+                    return world_state_change;
+                }
+                WorldStateChange::Lose
+                | WorldStateChange::RocketDestroyed
+                | WorldStateChange::Stalemate => {
+                    self.state = WorldState::Defeat;
+                    return world_state_change;
+                }
+                WorldStateChange::NoChange => {}
+            }
+        }
+
+        WorldStateChange::NoChange
+    }
+
+    /// Advances the simulation by a single step regardless of the world state, for use by
+    /// battles. Winning and losing are decided by the battle rules, so the world state is left
+    /// unchanged
+    ///
+    /// Arguments:
+    /// * `events`: Receives everything that happens during the step
+    pub(crate) fn battle_step<S: EventSink>(&mut self, events: &mut S) {
+        self.rocket_events.clear();
+        self.step(events);
+    }
+
+    /// Advances the simulation state of the world by a single step, following the ruleset. The
+    /// puzzle rules are described below
+    /// * Mice move forward 3 units
+    /// * Cats move forward 2 units
+    ///
+    /// Each frame check mouse/cat collisions
+    /// * Mice are killed by cats, causing defeat
+    ///
+    /// On walking off an open edge, walkers reappear on the opposite edge
+    ///
+    /// Walkers start at the centre of a square, and the following checks happen as they reach
+    /// the centre of the next
+    ///
+    /// On reaching a new grid, walkers check holes/rockets
+    /// * Cats are killed by holes
+    /// * Mice are killed by holes, causing defeat
+    /// * Mice are rescued by rockets
+    /// * Cats destroy rockets, causing defeat
+    ///
+    /// On reaching a new grid, walkers check arrow
+    /// * Mice are directed by the arrow
+    /// * Cats are directed by arrows, and if turned around, consume the arrow
+    ///
+    /// On reaching a new grid, walkers check walls, turning right, then left, then around
+    /// On all mice rescued, victory
+    /// Arrows placed by players expire after their lifetime
+    /// Spawners create walkers when their countdown runs out, or every spawner creates walkers
+    /// during a flood, unless there are already MAX_WALKERS of that type
+    /// On returning to a configuration seen before, stalemate
+    /// Everything that happens is reported to the event sink
+    fn step<S: EventSink>(&mut self, events: &mut S) -> WorldStateChange {
+        let mut world_state_change = WorldStateChange::NoChange;
+
+        // 1. Advance mice and cats
+        let ruleset = self.ruleset;
+        let mut reached_square = false;
+        let mut rocket_destroyed = false;
+        let all_walkers = self.mice.iter_mut().chain(self.cats.iter_mut());
+        for walker in all_walkers {
+            if walker.walk_at(ruleset.get_speed(walker.get_type())) == WalkResult::NewSquare {
+                reached_square = true;
+
+                // Walkers leaving through an open edge reappear on the opposite edge
+                walker.wrap(WIDTH as i8, HEIGHT as i8);
+
+                // 2. Check holes, rockets
+                rocket_destroyed |= Self::check_rockets_and_holes(
+                    &mut self.tiles,
+                    ruleset.rocket_penalty,
+                    &mut self.rocket_events,
+                    walker,
+                    events,
+                );
+
+                // Walkers that died or were rescued go no further
+                if walker.get_state() != WalkerState::Alive {
+                    continue;
+                }
+
+                // 3. Check arrows
+                Self::check_arrows(&mut self.tiles, ruleset.diminish_policy, walker, events);
+
+                // 4. Check walls
+                Self::check_walls(&self.data, ruleset.turn_priority, walker, events);
+            }
+        }
+
+        // 5. Count down arrows placed by players, removing those that have expired. Arrows
+        // removed by cats no longer belong to anybody
+        for (index, (tile, owned_arrow)) in self
+            .tiles
+            .iter_mut()
+            .zip(self.arrow_owners.iter_mut())
+            .enumerate()
+        {
+            if let Some(arrow) = owned_arrow {
+                let direction: Result<Direction, ()> = (*tile).try_into();
+                arrow.ticks_remaining -= 1;
+                if direction.is_err() {
+                    *owned_arrow = None;
+                } else if arrow.ticks_remaining == 0 {
+                    *tile = TileType::Empty;
+                    *owned_arrow = None;
+                    events.push_event(WorldEvent::ArrowRemoved {
+                        x: index % WIDTH,
+                        y: index / WIDTH,
+                    });
+                }
+            }
+        }
+
+        // 6. Check cat/mouse collisions
+        Self::check_collisions(&mut self.mice, &self.cats, events);
+
+        // 7. Check if too many mice have died to holes or cats, or any rockets were destroyed
+        let dead_mice = self
+            .mice
+            .iter()
+            .filter(|walker| walker.get_state() == WalkerState::Dead)
+            .count();
+        let lost = match ruleset.loss_condition {
+            LossCondition::AnyMouseDies => dead_mice > 0,
+            LossCondition::MiceDie(count) => {
+                dead_mice > 0 && self.removed_mice.dead + dead_mice >= count
+            }
+            LossCondition::Never => false,
+        };
+        if lost {
+            world_state_change = WorldStateChange::Lose;
+        }
+
+        if rocket_destroyed {
+            world_state_change = WorldStateChange::RocketDestroyed;
+        }
+
+        // 8. Check if enough mice have been rescued. A rocket destroyed as the last mouse boards
+        // still loses
+        let rescued_mice = self
+            .mice
+            .iter()
+            .filter(|walker| walker.get_state() == WalkerState::Rescued)
+            .count();
+        let won = match ruleset.win_condition {
+            WinCondition::AllMiceRescued => {
+                !self.mice.is_empty() && rescued_mice == self.mice.len()
+            }
+            WinCondition::MiceRescued(count) => {
+                rescued_mice > 0 && self.removed_mice.rescued + rescued_mice >= count
+            }
+            WinCondition::Never => false,
+        };
+        if world_state_change == WorldStateChange::NoChange && won {
+            world_state_change = WorldStateChange::Win;
+        }
+
+        // 9. Remove dead/rescued walkers, counting them
+        for (walkers, removed) in [
+            (&self.mice, &mut self.removed_mice),
+            (&self.cats, &mut self.removed_cats),
+        ] {
+            for walker in walkers.iter() {
+                match walker.get_state() {
+                    WalkerState::Alive => {}
+                    WalkerState::Rescued => removed.rescued += 1,
+                    WalkerState::Dead => removed.dead += 1,
+                }
+            }
+        }
+        self.mice
+            .retain(|walker| walker.get_state() == WalkerState::Alive);
+        self.cats
+            .retain(|walker| walker.get_state() == WalkerState::Alive);
+
+        // 10. Spawn new walkers. These first move on the next step. Floods pause the spawners'
+        // own countdowns
+        match self.spawn_flood.as_mut() {
+            Some(flood) => {
+                if flood.advance() {
+                    for spawner in self.spawners.iter() {
+                        Self::add_spawned_walker(
+                            &mut self.mice,
+                            &mut self.cats,
+                            &mut self.next_walker_id,
+                            spawner,
+                            flood.walker_type,
+                            events,
+                        );
+                    }
+                }
+            }
+            None => {
+                for spawner in self.spawners.iter_mut() {
+                    if spawner.advance() {
+                        let walker_type = spawner.get_type();
+                        Self::add_spawned_walker(
+                            &mut self.mice,
+                            &mut self.cats,
+                            &mut self.next_walker_id,
+                            spawner,
+                            walker_type,
+                            events,
+                        );
+                    }
+                }
+            }
+        }
+
+        // 11. Check if the world has returned to a configuration it has been in before. This is
+        // only checked as walkers reach the centre of a square, to save hashing every step
+        if world_state_change == WorldStateChange::NoChange
+            && reached_square
+            && self.loop_detector.observe(self.configuration_hash())
+        {
+            world_state_change = WorldStateChange::Stalemate;
+        }
+
+        // 12. Return the new world state for the user to handle
+        world_state_change
+    }
+
+    /// Hashes everything that decides how the world will change from now on, including the
+    /// random number generator. Walker IDs are left out, as they only identify walkers, as are
+    /// the rescued and dead counts unless the ruleset is decided by them. Walls and rules only
+    /// change through editing, which resets the loop detector instead. Configurations are
+    /// compared by this 64 bit hash alone, so two different configurations sharing a hash would
+    /// be reported as a stalemate. With a handful of comparisons per loop this is vanishingly
+    /// unlikely, and is accepted rather than storing a copy of the world to compare against
+    fn configuration_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        for walkers in [&self.mice, &self.cats] {
+            hasher.write_u32(walkers.len() as u32);
+            for walker in walkers.iter() {
+                hasher.write_u32(walker.get_x().scaled() as u32);
+                hasher.write_u32(walker.get_y().scaled() as u32);
+                hasher.write_u8(walker.get_direction() as u8);
+            }
+        }
+        for (tile, owned_arrow) in self.tiles.iter().zip(self.arrow_owners.iter()) {
+            hasher.write_u8(*tile as u8);
+            if let Some(arrow) = owned_arrow {
+                hasher.write_u8(arrow.owner.index() as u8);
+                hasher.write_u32(arrow.ticks_remaining);
+            }
+        }
+        for spawner in self.spawners.iter() {
+            hasher.write_u32(spawner.get_interval());
+            hasher.write_u32(spawner.get_countdown());
+        }
+        if let Some(flood) = self.spawn_flood {
+            hasher.write_u8(flood.walker_type as u8);
+            hasher.write_u32(flood.interval);
+            hasher.write_u32(flood.countdown);
+        }
+        let counts_rescued = matches!(self.ruleset.win_condition, WinCondition::MiceRescued(_));
+        let counts_dead = matches!(self.ruleset.loss_condition, LossCondition::MiceDie(_));
+        if counts_rescued || counts_dead {
+            hasher.write_u32(self.removed_mice.rescued as u32);
+            hasher.write_u32(self.removed_mice.dead as u32);
+        }
+        hasher.write_u32(self.rng.get_state());
+        hasher.finish()
+    }
+
+    /// Adds a walker at a spawner, unless there are already MAX_WALKERS of its type
+    ///
+    /// Arguments:
+    /// * `mice`: The mice
+    /// * `cats`: The cats
+    /// * `next_walker_id`: The ID given to the next walker created
+    /// * `spawner`: The spawner the walker appears at
+    /// * `walker_type`: The type of walker to spawn
+    /// * `events`: Receives the spawn
+    fn add_spawned_walker<S: EventSink>(
+        mice: &mut ArrayVec<Walker, TILES>,
+        cats: &mut ArrayVec<Walker, TILES>,
+        next_walker_id: &mut WalkerId,
+        spawner: &Spawner,
+        walker_type: WalkerType,
+        events: &mut S,
+    ) {
+        let walkers = match walker_type {
+            WalkerType::Mouse => mice,
+            WalkerType::Cat => cats,
+        };
+        if walkers.len() < Self::MAX_WALKERS {
+            let id = Self::take_walker_id(next_walker_id);
+            events.push_event(WorldEvent::Spawned {
+                id,
+                x: spawner.get_x(),
+                y: spawner.get_y(),
+                walker_type,
+                direction: spawner.get_direction(),
+            });
+            walkers.push(Walker::new(
+                id,
+                spawner.get_x() as i8,
+                spawner.get_y() as i8,
+                spawner.get_direction(),
+                walker_type,
+            ));
+        }
+    }
+
+    /// Takes the next unused walker ID
+    ///
+    /// Arguments:
+    /// * `next_walker_id`: The ID given to the next walker created, which is advanced
+    ///
+    /// Return value:
+    /// The ID for the new walker
+    fn take_walker_id(next_walker_id: &mut WalkerId) -> WalkerId {
+        let id = *next_walker_id;
+        *next_walker_id = id.next();
+        id
+    }
+
+    /// Handles collisions with walls
+    /// * If not blocked, keep going straight
+    /// * If blocked, make the first turn in the turn priority that is not blocked. Puzzles turn
+    ///   right, then left, then around
+    /// * If blocked all around, keep going straight. This will in practice not happen due to
+    ///   level design
+    ///
+    /// Arguments:
+    /// * `wall_data`: The internal representation of the walls
+    /// * `turn_priority`: The turns to try, in order, when blocked
+    /// * `walker`: The Walker to check
+    /// * `events`: Receives any bounce
+    fn check_walls<S: EventSink>(
+        wall_data: &[u8; DATA],
+        turn_priority: [Turn; 3],
+        walker: &mut Walker,
+        events: &mut S,
+    ) {
+        let (x, y) = Self::walker_square(walker);
+        let direction = walker.get_direction();
+
+        // Priority list of directions to travel. The first clear direction will be used
+        let candidate_directions = [
+            direction,
+            turn_priority[0].apply(direction),
+            turn_priority[1].apply(direction),
+            turn_priority[2].apply(direction),
+        ];
+
+        for candidate_direction in candidate_directions {
+            if !Self::get_wall_static(wall_data, x, y, candidate_direction) {
+                walker.set_direction(candidate_direction);
+                if candidate_direction != direction {
+                    events.push_event(WorldEvent::WallBounce {
+                        id: walker.get_id(),
+                        x,
+                        y,
+                        walker_type: walker.get_type(),
+                        direction: candidate_direction,
+                    });
+                }
+                break;
+            }
+        }
+    }
+
+    /// Handles collisions between arrows and walkers
+    /// * Walkers are turned to face the arrow direction
+    /// * Cats diminish the arrow according to the diminish policy. Puzzles diminish arrows that
+    ///   turn a cat 180 degrees
+    ///
+    /// Arguments:
+    /// * `tile`: The tiles
+    /// * `diminish_policy`: Which cats diminish arrows
+    /// * `walker`: The Walker to check
+    /// * `events`: Receives any turn, and any change to the arrow
+    fn check_arrows<S: EventSink>(
+        tiles: &mut [TileType; TILES],
+        diminish_policy: DiminishPolicy,
+        walker: &mut Walker,
+        events: &mut S,
+    ) {
+        let (x, y) = Self::walker_square(walker);
+        let arrow = Self::get_arrow_static(tiles, x, y);
+        let arrow_direction = arrow.try_into();
+        match arrow_direction {
+            Ok(direction) => {
+                let diminishes = match diminish_policy {
+                    DiminishPolicy::Never => false,
+                    DiminishPolicy::CatsHeadOn => walker.get_direction().turn_around() == direction,
+                    DiminishPolicy::CatsTurned => walker.get_direction() != direction,
+                };
+                if walker.get_type() == WalkerType::Cat && diminishes {
+                    let diminished = arrow.diminish();
+                    Self::set_tile_static(tiles, x, y, diminished);
+                    events.push_event(if diminished == TileType::Empty {
+                        WorldEvent::ArrowRemoved { x, y }
+                    } else {
+                        WorldEvent::ArrowDiminished { x, y }
+                    });
+                }
+                if walker.get_direction() != direction {
+                    events.push_event(WorldEvent::Turned {
+                        id: walker.get_id(),
+                        x,
+                        y,
+                        walker_type: walker.get_type(),
+                        direction,
+                    });
+                }
+                walker.set_direction(direction);
+            }
+            Err(_) => {
+                // Indicates an empty/hole/rocket tile, which is fine
+            }
+        };
+    }
+
+    /// Handles collisions between cats and mice. Only walkers that are still alive can collide
+    /// * Mice that collide with a cat are eaten and killed
+    ///
+    /// Arguments:
+    /// * `mice`: The mice to check
+    /// * `cats`: The cats to check
+    /// * `events`: Receives any deaths
+    fn check_collisions<S: EventSink>(
+        mice: &mut ArrayVec<Walker, TILES>,
+        cats: &ArrayVec<Walker, TILES>,
+        events: &mut S,
+    ) {
+        let living_mice = mice
+            .iter_mut()
+            .filter(|mouse| mouse.get_state() == WalkerState::Alive);
+        for mouse in living_mice {
+            if cats
+                .iter()
+                .filter(|cat| cat.get_state() == WalkerState::Alive)
+                .any(|cat| Self::walkers_collide(cat, mouse))
+            {
+                mouse.kill();
+                events.push_event(WorldEvent::Died {
+                    id: mouse.get_id(),
+                    x: Self::walker_square(mouse).0,
+                    y: Self::walker_square(mouse).1,
+                    walker_type: WalkerType::Mouse,
+                    cause: DeathCause::Eaten,
+                });
+            }
+        }
+    }
+
+    /// Gets the square the centre of a walker is in. A walker part way through an open edge is
+    /// in the square on the opposite edge
+    ///
+    /// Arguments:
+    /// * `walker`: The walker
+    ///
+    /// Return value:
+    /// The x and y coordinates of the square
+    fn walker_square(walker: &Walker) -> (usize, usize) {
+        (
+            walker.get_x().floor().rem_euclid(WIDTH as i8) as usize,
+            walker.get_y().floor().rem_euclid(HEIGHT as i8) as usize,
+        )
+    }
+
+    /// Determines if two walkers are close enough to collide. The threshold depends on how the
+    /// walkers are approaching each other
+    /// * Walking towards or away from each other, they collide within half a square
+    /// * Walking the same direction, they collide within a third of a square
+    /// * Walking along the same line, they must also be within a third of a square across it
+    /// * Walking at right angles, they collide when within a third of a square on both axes
+    ///
+    /// Distances are measured around the world, so walkers either side of an open edge can
+    /// collide. Walkers only pass the centre of an edge square through an open edge, so this
+    /// never brings walkers on either side of a closed edge close enough to collide
+    ///
+    /// Arguments:
+    /// * `a`: The first walker
+    /// * `b`: The second walker
+    ///
+    /// Return value:
+    /// True if the walkers collide
+    fn walkers_collide(a: &Walker, b: &Walker) -> bool {
+        let dx = Self::wrapped_distance(a.get_x(), b.get_x(), WIDTH);
+        let dy = Self::wrapped_distance(a.get_y(), b.get_y(), HEIGHT);
+        let a_direction = a.get_direction();
+        let b_direction = b.get_direction();
+
+        if a_direction == b_direction || a_direction == b_direction.turn_around() {
+            // Walkers on the same line. Distance along the line decides the collision, but they
+            // must be in the same lane
+            let (along, across) = match a_direction {
+                Direction::Up | Direction::Down => (dy, dx),
+                Direction::Left | Direction::Right => (dx, dy),
+            };
+            let threshold = if a_direction == b_direction {
+                COLLISION_SAME_DIRECTION
+            } else {
+                COLLISION_HEAD_ON
+            };
+
+            across <= COLLISION_RIGHT_ANGLE && along <= threshold
+        } else {
+            dx <= COLLISION_RIGHT_ANGLE && dy <= COLLISION_RIGHT_ANGLE
+        }
+    }
+
+    /// Gets the distance between two coordinates on one axis, the shorter way around the world
+    ///
+    /// Arguments:
+    /// * `a`: The first coordinate
+    /// * `b`: The second coordinate
+    /// * `size`: The number of squares along the axis
+    ///
+    /// Return value:
+    /// The distance in fractional units
+    fn wrapped_distance(a: FixedPoint, b: FixedPoint, size: usize) -> i32 {
+        let world_size = size as i32 * UNITS_PER_SQUARE;
+        let distance = (a.scaled() - b.scaled()).rem_euclid(world_size);
+        distance.min(world_size - distance)
+    }
+
+    /// Handles collisions between holes/rockets and walkers
+    /// * Holes kill everything
+    /// * Rockets rescue mice
+    /// * Cats entering a rocket are consumed, and the rocket penalised
+    /// * Destroyed rockets are ignored
+    ///
+    /// Arguments:
+    /// * `tiles`: The tiles
+    /// * `rocket_penalty`: What happens to rockets entered by cats
+    /// * `rocket_events`: The list to record walkers entering rockets in. Walkers entering
+    ///   rockets once it is full are only reported to the event sink
+    /// * `walker`: The Walker to check
+    /// * `events`: Receives any death, or the rocket event
+    ///
+    /// Return value:
+    /// True if the walker destroyed a rocket
+    fn check_rockets_and_holes<S: EventSink>(
+        tiles: &mut [TileType; TILES],
+        rocket_penalty: RocketPenalty,
+        rocket_events: &mut ArrayVec<RocketEvent, TILES>,
+        walker: &mut Walker,
+        events: &mut S,
+    ) -> bool {
+        let (x, y) = Self::walker_square(walker);
+        let tile = tiles[y * WIDTH + x];
+
+        match (tile, walker.get_type()) {
+            (TileType::Hole, _) => {
+                walker.kill();
+                events.push_event(WorldEvent::Died {
+                    id: walker.get_id(),
+                    x,
+                    y,
+                    walker_type: walker.get_type(),
+                    cause: DeathCause::Hole,
+                });
+                false
+            }
+            (TileType::Rocket, WalkerType::Mouse) => {
+                walker.rescue();
+                let rocket_event = RocketEvent {
+                    x,
+                    y,
+                    kind: RocketEventKind::Rescue,
+                };
+                let _ = rocket_events.try_push(rocket_event);
+                events.push_event(WorldEvent::Rocket {
+                    id: walker.get_id(),
+                    event: rocket_event,
+                });
+                false
+            }
+            (TileType::Rocket, WalkerType::Cat) => {
+                walker.kill();
+                let kind = match rocket_penalty {
+                    RocketPenalty::Destroy => {
+                        Self::set_tile_static(tiles, x, y, TileType::RocketDestroyed);
+                        RocketEventKind::Destroyed
+                    }
+                    RocketPenalty::Hit => RocketEventKind::Hit,
+                };
+                let _ = rocket_events.try_push(RocketEvent { x, y, kind });
+                events.push_event(WorldEvent::Rocket {
+                    id: walker.get_id(),
+                    event: RocketEvent { x, y, kind },
+                });
+                kind == RocketEventKind::Destroyed
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// GIVEN A row of cells and a direction
+    /// WHEN we calculate the wall index and bitmask
+    /// THEN the correct values are returned
+    #[test]
+    fn index_and_mask() {
+        assert_eq!((0, 0b00000001), <World>::get_wrapped_wall_index_and_mask(0, 0, Direction::Up));
+        assert_eq!((0, 0b00000100), <World>::get_wrapped_wall_index_and_mask(1, 0, Direction::Up));
+        assert_eq!((0, 0b00010000), <World>::get_wrapped_wall_index_and_mask(2, 0, Direction::Up));
+        assert_eq!((0, 0b01000000), <World>::get_wrapped_wall_index_and_mask(3, 0, Direction::Up));
+        assert_eq!((1, 0b00000001), <World>::get_wrapped_wall_index_and_mask(4, 0, Direction::Up));
+
+        assert_eq!(
+            (0, 0b00000010),
+            <World>::get_wrapped_wall_index_and_mask(0, 0, Direction::Left)
+        );
+        assert_eq!(
+            (0, 0b00001000),
+            <World>::get_wrapped_wall_index_and_mask(1, 0, Direction::Left)
+        );
+        assert_eq!(
+            (0, 0b00100000),
+            <World>::get_wrapped_wall_index_and_mask(2, 0, Direction::Left)
+        );
+        assert_eq!(
+            (0, 0b10000000),
+            <World>::get_wrapped_wall_index_and_mask(3, 0, Direction::Left)
+        );
+        assert_eq!(
+            (1, 0b00000010),
+            <World>::get_wrapped_wall_index_and_mask(4, 0, Direction::Left)
+        );
+
+        // Down walls are the top wall of the cell below, increasing the index by 3
+        assert_eq!(
+            (3, 0b00000001),
+            <World>::get_wrapped_wall_index_and_mask(0, 0, Direction::Down)
+        );
+        assert_eq!(
+            (3, 0b00000100),
+            <World>::get_wrapped_wall_index_and_mask(1, 0, Direction::Down)
+        );
+        assert_eq!(
+            (3, 0b00010000),
+            <World>::get_wrapped_wall_index_and_mask(2, 0, Direction::Down)
+        );
+        assert_eq!(
+            (3, 0b01000000),
+            <World>::get_wrapped_wall_index_and_mask(3, 0, Direction::Down)
+        );
+        assert_eq!(
+            (4, 0b00000001),
+            <World>::get_wrapped_wall_index_and_mask(4, 0, Direction::Down)
+        );
+
+        // Right walls are the left wall of the cell to the left, shifting the mask and increasing
+        // the index by 1 for every 4th element
+        assert_eq!(
+            (0, 0b00001000),
+            <World>::get_wrapped_wall_index_and_mask(0, 0, Direction::Right)
+        );
+        assert_eq!(
+            (0, 0b00100000),
+            <World>::get_wrapped_wall_index_and_mask(1, 0, Direction::Right)
+        );
+        assert_eq!(
+            (0, 0b10000000),
+            <World>::get_wrapped_wall_index_and_mask(2, 0, Direction::Right)
+        );
+        assert_eq!(
+            (1, 0b00000010),
+            <World>::get_wrapped_wall_index_and_mask(3, 0, Direction::Right)
+        );
+        assert_eq!(
+            (1, 0b00001000),
+            <World>::get_wrapped_wall_index_and_mask(4, 0, Direction::Right)
+        );
+    }
+
+    /// GIVEN a newly created world
+    /// WHEN we check the walls
+    /// THEN the walls are around the edge only
+    #[test]
     fn new_creates_outline() {
         let world = World::new();
 
-        // Top and bottom set
-        for x in 0..WORLD_WIDTH {
-            assert_eq!(true, world.get_wall(x, 0, Direction::Up));
-            assert_eq!(true, world.get_wall(x, WORLD_HEIGHT - 1, Direction::Down));
+        // Top and bottom set
+        for x in 0..WORLD_WIDTH {
+            assert_eq!(true, world.get_wall(x, 0, Direction::Up));
+            assert_eq!(true, world.get_wall(x, WORLD_HEIGHT - 1, Direction::Down));
+        }
+
+        // Left and right set
+        for y in 0..WORLD_HEIGHT {
+            assert_eq!(true, world.get_wall(0, y, Direction::Left));
+            assert_eq!(true, world.get_wall(WORLD_WIDTH - 1, y, Direction::Right));
+        }
+
+        // Everything else not set
+        for y in 1..WORLD_HEIGHT - 1 {
+            for x in 1..WORLD_WIDTH - 1 {
+                assert_eq!(false, world.get_wall(x, y, Direction::Up));
+                assert_eq!(false, world.get_wall(x, y, Direction::Down));
+                assert_eq!(false, world.get_wall(x, y, Direction::Left));
+                assert_eq!(false, world.get_wall(x, y, Direction::Right));
+            }
+        }
+    }
+
+    /// GIVEN an empty world
+    /// WHEN a walker is created
+    /// THEN the walker is added to the correct walker array
+    /// AND the walker is added to the backing data
+    #[test]
+    fn walker_creation() {
+        let mut world = World::new();
+        world.create_walker(1, 1, Direction::Down, WalkerType::Mouse);
+        world.create_walker(4, 4, Direction::Up, WalkerType::Cat);
+
+        // The walkers exist
+        assert_eq!(1, world.mice.len());
+        assert_eq!(1, world.cats.len());
+
+        // The source data has been updated to include the walker
+        let walker_data = &world.data[<World>::TILE_BLOCK_OFFSET..];
+        // Walkers are packed into one byte, so let's find them
+        assert_eq!(ENTITY_TYPE_MOUSE, walker_data[WORLD_WIDTH * 1 + 1] & ENTITY_TYPE_MASK);
+        assert_eq!(ENTITY_DIRECTION_DOWN, walker_data[WORLD_WIDTH * 1 + 1] & ENTITY_DIRECTION_MASK);
+        assert_eq!(ENTITY_TYPE_CAT, walker_data[WORLD_WIDTH * 4 + 4] & ENTITY_TYPE_MASK);
+        assert_eq!(ENTITY_DIRECTION_UP, walker_data[WORLD_WIDTH * 4 + 4] & ENTITY_DIRECTION_MASK);
+    }
+
+    /// GIVEN an existing walker
+    /// WHEN a new walker is created at the same spot
+    /// THEN the operation is rejected
+    #[test]
+    fn walkers_cannot_be_created_in_same_square() {
+        let mut world = World::new();
+        let created_1 = world.create_walker(0, 0, Direction::Down, WalkerType::Cat);
+        let created_2 = world.create_walker(0, 0, Direction::Down, WalkerType::Cat);
+
+        assert_eq!(true, created_1);
+        assert_eq!(false, created_2);
+    }
+
+    /// GIVEN a wall directly ahead
+    /// WHEN a walker walks towards/along/away from the wall
+    /// THEN the correct turns (right/none/none) are made
+    #[test]
+    fn walker_wall_straight() {
+        let world = World::new();
+        let mut walker_up = Walker::new(WalkerId::new(0), 4, 0, Direction::Up, WalkerType::Mouse);
+        let mut walker_down =
+            Walker::new(WalkerId::new(0), 4, 0, Direction::Down, WalkerType::Mouse);
+        let mut walker_left =
+            Walker::new(WalkerId::new(0), 4, 0, Direction::Left, WalkerType::Mouse);
+        let mut walker_right =
+            Walker::new(WalkerId::new(0), 4, 0, Direction::Right, WalkerType::Mouse);
+
+        let turns = Ruleset::puzzle().turn_priority;
+        <World>::check_walls(&world.data, turns, &mut walker_up, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_down, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_left, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_right, &mut ());
+
+        assert_eq!(Direction::Right, walker_up.get_direction());
+        assert_eq!(Direction::Down, walker_down.get_direction());
+        assert_eq!(Direction::Left, walker_left.get_direction());
+        assert_eq!(Direction::Right, walker_right.get_direction());
+    }
+
+    /// GIVEN a wall directly ahead and to the right
+    /// WHEN the walker walks towards/left/right/away from the wall
+    /// THEN the correct turns (left/none/down/none) are made
+    #[test]
+    fn walker_wall_forced_left() {
+        let world = World::new();
+        let mut walker_up = Walker::new(WalkerId::new(0), 11, 0, Direction::Up, WalkerType::Mouse);
+        let mut walker_down =
+            Walker::new(WalkerId::new(0), 11, 0, Direction::Down, WalkerType::Mouse);
+        let mut walker_left =
+            Walker::new(WalkerId::new(0), 11, 0, Direction::Left, WalkerType::Mouse);
+        let mut walker_right =
+            Walker::new(WalkerId::new(0), 11, 0, Direction::Right, WalkerType::Mouse);
+
+        let turns = Ruleset::puzzle().turn_priority;
+        <World>::check_walls(&world.data, turns, &mut walker_up, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_down, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_left, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_right, &mut ());
+
+        assert_eq!(Direction::Left, walker_up.get_direction());
+        assert_eq!(Direction::Down, walker_down.get_direction());
+        assert_eq!(Direction::Left, walker_left.get_direction());
+        assert_eq!(Direction::Down, walker_right.get_direction());
+    }
+
+    /// GIVEN a wall in a U shape (directly ahead and to the left and right)
+    /// WHEN the walker walks towards/left/right/away from the wall
+    /// THEN the correct turns (around/left/right/none) are made
+    #[test]
+    fn walker_wall_u_shape() {
+        let mut world = World::new();
+        world.set_wall(0, 0, Direction::Right, true);
+        let mut walker_up = Walker::new(WalkerId::new(0), 0, 0, Direction::Up, WalkerType::Mouse);
+        let mut walker_down =
+            Walker::new(WalkerId::new(0), 0, 0, Direction::Down, WalkerType::Mouse);
+        let mut walker_left =
+            Walker::new(WalkerId::new(0), 0, 0, Direction::Left, WalkerType::Mouse);
+        let mut walker_right =
+            Walker::new(WalkerId::new(0), 0, 0, Direction::Right, WalkerType::Mouse);
+
+        let turns = Ruleset::puzzle().turn_priority;
+        <World>::check_walls(&world.data, turns, &mut walker_up, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_down, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_left, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_right, &mut ());
+
+        assert_eq!(Direction::Down, walker_up.get_direction());
+        assert_eq!(Direction::Down, walker_down.get_direction());
+        assert_eq!(Direction::Down, walker_left.get_direction());
+        assert_eq!(Direction::Down, walker_right.get_direction());
+    }
+
+    /// GIVEN a wall directly ahead and a turn priority preferring left turns
+    /// WHEN a walker walks towards the wall
+    /// THEN the walker turns left
+    /// AND when left is also blocked, turns right
+    #[test]
+    fn walker_wall_turn_priority() {
+        let world = World::new();
+        let mut walker_up = Walker::new(WalkerId::new(0), 4, 0, Direction::Up, WalkerType::Mouse);
+        let mut walker_corner =
+            Walker::new(WalkerId::new(0), 0, 0, Direction::Up, WalkerType::Mouse);
+
+        let turns = [Turn::Left, Turn::Right, Turn::Back];
+        <World>::check_walls(&world.data, turns, &mut walker_up, &mut ());
+        <World>::check_walls(&world.data, turns, &mut walker_corner, &mut ());
+
+        assert_eq!(Direction::Left, walker_up.get_direction());
+        assert_eq!(Direction::Right, walker_corner.get_direction());
+    }
+
+    /// GIVEN an arrow with no nearby walls
+    /// WHEN a walker encounters that arrow at right angles
+    /// THEN the walker turns in that direction
+    #[test]
+    fn walker_arrow_right_angle_turns() {
+        let mut world = World::new();
+        world.set_arrow(2, 2, TileType::Up);
+        world.set_arrow(4, 3, TileType::Down);
+        world.set_arrow(6, 4, TileType::Left);
+        world.set_arrow(8, 5, TileType::Right);
+
+        // Walkers approaching up arrow from left/right
+        world.create_walker(1, 2, Direction::Right, WalkerType::Mouse);
+        world.create_walker(3, 2, Direction::Left, WalkerType::Mouse);
+
+        // Walkers approaching down arrow from left/right
+        world.create_walker(3, 3, Direction::Right, WalkerType::Mouse);
+        world.create_walker(5, 3, Direction::Left, WalkerType::Mouse);
+
+        // Walkers approaching left arrow from top/bottom
+        world.create_walker(6, 3, Direction::Down, WalkerType::Mouse);
+        world.create_walker(6, 5, Direction::Up, WalkerType::Mouse);
+
+        // Walkers approaching right arrow from top/bottom
+        world.create_walker(8, 4, Direction::Down, WalkerType::Mouse);
+        world.create_walker(8, 6, Direction::Up, WalkerType::Mouse);
+        world.start();
+
+        // Run for the time is takes for a mouse to move just before one square
+        for _ in 0..59 {
+            world.tick();
+        }
+
+        // Walkers should not have changed their directions yet
+        assert_eq!(Direction::Right, world.mice[0].get_direction());
+        assert_eq!(Direction::Left, world.mice[1].get_direction());
+        assert_eq!(Direction::Right, world.mice[2].get_direction());
+        assert_eq!(Direction::Left, world.mice[3].get_direction());
+        assert_eq!(Direction::Down, world.mice[4].get_direction());
+        assert_eq!(Direction::Up, world.mice[5].get_direction());
+        assert_eq!(Direction::Down, world.mice[6].get_direction());
+        assert_eq!(Direction::Up, world.mice[7].get_direction());
+
+        // Tick the final time required for mice to walk one square
+        world.tick();
+
+        // Walkers should now have been directed by the arrows
+        assert_eq!(Direction::Up, world.mice[0].get_direction());
+        assert_eq!(Direction::Up, world.mice[1].get_direction());
+        assert_eq!(Direction::Down, world.mice[2].get_direction());
+        assert_eq!(Direction::Down, world.mice[3].get_direction());
+        assert_eq!(Direction::Left, world.mice[4].get_direction());
+        assert_eq!(Direction::Left, world.mice[5].get_direction());
+        assert_eq!(Direction::Right, world.mice[6].get_direction());
+        assert_eq!(Direction::Right, world.mice[7].get_direction());
+    }
+
+    /// GIVEN an arrow with no nearby walls
+    /// WHEN a mouse encounters an arrow in opposite direction
+    /// THEN the mouse is turned around
+    /// AND the arrow is unchanged
+    #[test]
+    fn mice_do_not_diminish_arrows_if_opposed() {
+        let mut world = World::new();
+        world.set_arrow(4, 3, TileType::Up);
+        world.set_arrow(4, 5, TileType::Down);
+        world.set_arrow(3, 4, TileType::Left);
+        world.set_arrow(5, 4, TileType::Right);
+
+        world.create_walker(4, 2, Direction::Down, WalkerType::Mouse);
+        world.create_walker(4, 6, Direction::Up, WalkerType::Mouse);
+        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(6, 4, Direction::Left, WalkerType::Mouse);
+        world.start();
+
+        // Run for the time is takes for a mouse to move just before one square
+        for _ in 0..59 {
+            world.tick();
+        }
+
+        // The walkers have not yet encountered the arrows and have their original direction
+        assert_eq!(Direction::Down, world.mice[0].get_direction());
+        assert_eq!(Direction::Up, world.mice[1].get_direction());
+        assert_eq!(Direction::Right, world.mice[2].get_direction());
+        assert_eq!(Direction::Left, world.mice[3].get_direction());
+
+        // Tick the final time required for mice to walk one square
+        world.tick();
+
+        // Walkers should now have been directed by the arrows
+        assert_eq!(Direction::Up, world.mice[0].get_direction());
+        assert_eq!(Direction::Down, world.mice[1].get_direction());
+        assert_eq!(Direction::Left, world.mice[2].get_direction());
+        assert_eq!(Direction::Right, world.mice[3].get_direction());
+
+        // The arrows are unchanged.
+        assert_eq!(TileType::Up, world.get_arrow(4, 3));
+        assert_eq!(TileType::Down, world.get_arrow(4, 5));
+        assert_eq!(TileType::Left, world.get_arrow(3, 4));
+        assert_eq!(TileType::Right, world.get_arrow(5, 4));
+    }
+
+    /// GIVEN an arrow with no nearby walls
+    /// WHEN a cat encounters an arrow in opposite direction
+    /// THEN the cat is turned around
+    /// AND the arrow is diminished
+    #[test]
+    fn cats_diminish_arrows_if_opposed() {
+        let mut world = World::new();
+        world.set_arrow(4, 4, TileType::Down);
+        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.start();
+
+        // Walk cat to edge of arrow. Cats move at 2/3 speed of a mouse, so 90 ticks required
+        for _ in 0..89 {
+            world.tick();
+        }
+
+        // The arrow and cat are unchanged
+        assert_eq!(TileType::Down, world.get_arrow(4, 4));
+        assert_eq!(Direction::Up, world.cats[0].get_direction());
+
+        // Tick the final time required for cats to walk one square
+        world.tick();
+
+        // The arrow is diminished and the cat turned around
+        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
+        assert_eq!(Direction::Down, world.cats[0].get_direction());
+    }
+
+    /// GIVEN arrows with no nearby walls, one opposing a cat and one at right angles to another
+    /// WHEN the cats walk onto them under each diminish policy
+    /// THEN puzzle rules only diminish the opposing arrow, the turned policy diminishes both and
+    /// the never policy diminishes neither
+    #[test]
+    fn diminish_policy_controls_arrows() {
+        for (diminish_policy, opposed, right_angle) in [
+            (DiminishPolicy::CatsHeadOn, TileType::DownHalf, TileType::Left),
+            (DiminishPolicy::CatsTurned, TileType::DownHalf, TileType::LeftHalf),
+            (DiminishPolicy::Never, TileType::Down, TileType::Left),
+        ] {
+            let mut world = World::new();
+            world.set_ruleset(Ruleset {
+                diminish_policy,
+                ..Ruleset::puzzle()
+            });
+            world.set_arrow(4, 4, TileType::Down);
+            world.set_arrow(8, 4, TileType::Left);
+            world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+            world.create_walker(8, 5, Direction::Up, WalkerType::Cat);
+            world.start();
+
+            for _ in 0..90 {
+                world.tick();
+            }
+
+            assert_eq!(opposed, world.get_arrow(4, 4));
+            assert_eq!(right_angle, world.get_arrow(8, 4));
+            assert_eq!(Direction::Down, world.cats[0].get_direction());
+            assert_eq!(Direction::Left, world.cats[1].get_direction());
+        }
+    }
+
+    /// GIVEN an arrow with no nearby walls
+    /// WHEN three cats encounter an arrow in opposite direction
+    /// THEN the first two cats are turned around
+    /// AND the last two cats continues
+    /// AND the arrow is removed
+    #[test]
+    fn double_diminish_removes_arrow() {
+        let mut world = World::new();
+        world.set_arrow(4, 4, TileType::Down);
+        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.create_walker(4, 6, Direction::Up, WalkerType::Cat);
+        world.create_walker(4, 7, Direction::Up, WalkerType::Cat);
+        world.start();
+
+        // Walk first cat into the arrow
+        for _ in 0..90 {
+            world.tick();
+        }
+
+        // The arrow is diminished and first cat turned around
+        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
+        assert_eq!(Direction::Down, world.cats[0].get_direction());
+        assert_eq!(Direction::Up, world.cats[1].get_direction());
+        assert_eq!(Direction::Up, world.cats[2].get_direction());
+
+        // Walk second cat into the arrow
+        for _ in 0..90 {
+            world.tick();
+        }
+
+        // The arrow is removed and the second cat turned around
+        assert_eq!(TileType::Empty, world.get_arrow(4, 4));
+        assert_eq!(Direction::Down, world.cats[0].get_direction());
+        assert_eq!(Direction::Down, world.cats[1].get_direction());
+        assert_eq!(Direction::Up, world.cats[2].get_direction());
+
+        // Walk third cat into the 'arrow'
+        for _ in 0..90 {
+            world.tick();
+        }
+
+        // The arrow is still gone and the cat continues on his way
+        assert_eq!(TileType::Empty, world.get_arrow(4, 4));
+        assert_eq!(Direction::Down, world.cats[0].get_direction());
+        assert_eq!(Direction::Down, world.cats[1].get_direction());
+        assert_eq!(Direction::Up, world.cats[2].get_direction());
+    }
+
+    /// GIVEN an arrow placed by a player with a lifetime of 10 steps
+    /// WHEN the world is run
+    /// THEN the arrow is removed on the 10th step
+    #[test]
+    fn owned_arrows_expire() {
+        let mut world = World::new();
+        assert_eq!(Ok(()), world.place_owned_arrow(2, 2, Direction::Up, Player::One, 10, 3));
+        world.start();
+
+        for _ in 0..9 {
+            world.tick();
+        }
+        assert_eq!(TileType::Up, world.get_arrow(2, 2));
+        assert_eq!(Some(Player::One), world.get_arrow_owner(2, 2));
+
+        world.tick();
+        assert_eq!(TileType::Empty, world.get_arrow(2, 2));
+        assert_eq!(None, world.get_arrow_owner(2, 2));
+    }
+
+    /// GIVEN a player limited to two arrows
+    /// WHEN arrows are placed
+    /// THEN a third arrow removes the player's oldest, replacing a player's own arrow removes
+    /// nothing, and other players' arrows, puzzle arrows, rockets and holes cannot be replaced
+    #[test]
+    fn owned_arrows_evict_oldest() {
+        let mut world = World::new();
+        world.set_tile(5, 0, TileType::Rocket);
+        world.set_arrow(6, 0, TileType::Left);
+        world
+            .place_owned_arrow(3, 0, Direction::Up, Player::Two, 100, 2)
+            .unwrap();
+        world
+            .place_owned_arrow(0, 0, Direction::Up, Player::One, 100, 2)
+            .unwrap();
+        world
+            .place_owned_arrow(1, 0, Direction::Down, Player::One, 100, 2)
+            .unwrap();
+        world
+            .place_owned_arrow(2, 0, Direction::Left, Player::One, 100, 2)
+            .unwrap();
+
+        assert_eq!(TileType::Empty, world.get_arrow(0, 0));
+        assert_eq!(None, world.get_arrow_owner(0, 0));
+        assert_eq!(TileType::Down, world.get_arrow(1, 0));
+        assert_eq!(TileType::Left, world.get_arrow(2, 0));
+        assert_eq!(Some(Player::Two), world.get_arrow_owner(3, 0));
+
+        world
+            .place_owned_arrow(1, 0, Direction::Right, Player::One, 100, 2)
+            .unwrap();
+        assert_eq!(TileType::Right, world.get_arrow(1, 0));
+        assert_eq!(TileType::Left, world.get_arrow(2, 0));
+
+        for x in [3, 5, 6] {
+            assert_eq!(
+                Err(PlacementError::TileOccupied),
+                world.place_owned_arrow(x, 0, Direction::Up, Player::One, 100, 2)
+            );
+        }
+        assert_eq!(TileType::Up, world.get_arrow(3, 0));
+    }
+
+    /// GIVEN an arrow placed by a player
+    /// WHEN two cats walk into it from the opposite direction
+    /// THEN the arrow is diminished, keeping its owner, then removed along with its owner
+    #[test]
+    fn cats_diminish_owned_arrows() {
+        let mut world = World::new();
+        world
+            .place_owned_arrow(4, 4, Direction::Down, Player::Three, 1000, 3)
+            .unwrap();
+        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.create_walker(4, 6, Direction::Up, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..90 {
+            world.tick();
+        }
+        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
+        assert_eq!(Some(Player::Three), world.get_arrow_owner(4, 4));
+
+        for _ in 0..90 {
+            world.tick();
+        }
+        assert_eq!(TileType::Empty, world.get_arrow(4, 4));
+        assert_eq!(None, world.get_arrow_owner(4, 4));
+    }
+
+    /// GIVEN a cat walking towards a hole and a mouse walking towards a rocket
+    /// WHEN the world is ticked with an event buffer until the mouse is rescued
+    /// THEN the cat's death and the rescue are reported in order
+    #[test]
+    fn tick_with_events_reports_deaths_and_rescues() {
+        let mut world = World::new();
+        world.set_tile(1, 2, TileType::Hole);
+        world.set_tile(2, 0, TileType::Rocket);
+        world.create_walker(0, 2, Direction::Right, WalkerType::Cat);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+        for _ in 0..120 {
+            world.tick_with_events(&mut events);
+        }
+
+        assert_eq!(
+            [
+                WorldEvent::Died {
+                    id: WalkerId::new(0),
+                    x: 1,
+                    y: 2,
+                    walker_type: WalkerType::Cat,
+                    cause: DeathCause::Hole
+                },
+                WorldEvent::Rocket {
+                    id: WalkerId::new(1),
+                    event: RocketEvent {
+                        x: 2,
+                        y: 0,
+                        kind: RocketEventKind::Rescue
+                    }
+                }
+            ],
+            events.as_slice()
+        );
+    }
+
+    /// GIVEN an arrow against a wall
+    /// WHEN a mouse is turned into the wall by the arrow, with the events passed to a closure
+    /// THEN the turn by the arrow and the bounce off the wall are both reported
+    #[test]
+    fn tick_with_events_reports_turns_and_bounces() {
+        let mut world = World::new();
+        world.set_arrow(4, 0, TileType::Up);
+        world.create_walker(5, 0, Direction::Left, WalkerType::Mouse);
+        world.start();
+
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+        for _ in 0..60 {
+            world.tick_with_events(&mut |event| events.push(event));
+        }
+
+        assert_eq!(
+            [
+                WorldEvent::Turned {
+                    id: WalkerId::new(0),
+                    x: 4,
+                    y: 0,
+                    walker_type: WalkerType::Mouse,
+                    direction: Direction::Up
+                },
+                WorldEvent::WallBounce {
+                    id: WalkerId::new(0),
+                    x: 4,
+                    y: 0,
+                    walker_type: WalkerType::Mouse,
+                    direction: Direction::Right
+                }
+            ],
+            events.as_slice()
+        );
+    }
+
+    /// GIVEN an arrow placed by a player, and two cats walking into it from the opposite
+    /// direction
+    /// WHEN the world is ticked with events until the arrow is worn away
+    /// THEN the arrow is reported diminished, then removed
+    #[test]
+    fn tick_with_events_reports_arrow_changes() {
+        let mut world = World::new();
+        world
+            .place_owned_arrow(4, 4, Direction::Down, Player::Three, 1000, 3)
+            .unwrap();
+        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.create_walker(4, 6, Direction::Up, WalkerType::Cat);
+        world.start();
+
+        let mut events: ArrayVec<WorldEvent, 8> = ArrayVec::new();
+        for _ in 0..180 {
+            world.tick_with_events(&mut events);
+        }
+
+        let arrow_events: ArrayVec<WorldEvent, 8> = events
+            .into_iter()
+            .filter(|event| !matches!(event, WorldEvent::Turned { .. }))
+            .collect();
+        assert_eq!(
+            [
+                WorldEvent::ArrowDiminished { x: 4, y: 4 },
+                WorldEvent::ArrowRemoved { x: 4, y: 4 }
+            ],
+            arrow_events.as_slice()
+        );
+    }
+
+    /// GIVEN an arrow placed by a player
+    /// WHEN its lifetime runs out
+    /// THEN its removal is reported
+    #[test]
+    fn tick_with_events_reports_expired_arrows() {
+        let mut world = World::new();
+        world
+            .place_owned_arrow(4, 4, Direction::Down, Player::One, 10, 3)
+            .unwrap();
+        world.start();
+
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+        for _ in 0..9 {
+            world.tick_with_events(&mut events);
+        }
+        assert!(events.is_empty());
+
+        world.tick_with_events(&mut events);
+        assert_eq!([WorldEvent::ArrowRemoved { x: 4, y: 4 }], events.as_slice());
+    }
+
+    /// GIVEN an arrow against a wall
+    /// WHEN a walker is turned into the wall by the arrow
+    /// THEN the normal wall rule are applied, causing the walker to turn
+    #[test]
+    fn walker_turn_into_wall() {
+        let mut world = World::new();
+        world.set_arrow(4, 0, TileType::Up);
+        world.create_walker(5, 0, Direction::Left, WalkerType::Mouse);
+        world.start();
+
+        // Walk the walker into the arrow
+        for _ in 0..60 {
+            world.tick();
+        }
+
+        // The walker is turned up by the arrow, then right by the wall
+        assert_eq!(Direction::Right, world.mice[0].get_direction());
+    }
+
+    /// GIVEN a newly created world
+    /// WHEN an arrow is set
+    /// THEN reading that arrow back returns the correct value
+    #[test]
+    fn arrow_get_set() {
+        let mut world = World::new();
+
+        assert_eq!(TileType::Empty, world.get_arrow(0, 0));
+        world.set_arrow(0, 0, TileType::Right);
+        assert_eq!(TileType::Right, world.get_arrow(0, 0));
+
+        assert_eq!(TileType::Empty, world.get_arrow(7, 3));
+        world.set_arrow(7, 3, TileType::Right);
+        assert_eq!(TileType::Right, world.get_arrow(7, 3));
+    }
+
+    /// GIVEN a world with a hole one unit to the right of a cat
+    /// WHEN the cat walks into the hole
+    /// THEN the cat is killed
+    /// AND the world state does not change
+    #[test]
+    fn holes_kill_cats() {
+        let mut world = World::new();
+
+        world.set_tile(1, 0, TileType::Hole);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..89 {
+            world.tick();
+        }
+
+        // After 89 ticks the cat is still alive
+        assert_eq!(1, world.cats.len());
+
+        // The 90th tick kills the cat as it falls into the hole
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::NoChange, world_state_change);
+        assert_eq!(0, world.cats.len());
+    }
+
+    /// GIVEN a world with a hole one unit to the right of a mouse
+    /// WHEN the mouse walks into the hole
+    /// THEN the mouse is killed
+    /// AND the world state changes to lose
+    #[test]
+    fn holes_kill_mice_and_cause_loss() {
+        let mut world = World::new();
+
+        world.set_tile(1, 0, TileType::Hole);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        for _ in 0..59 {
+            world.tick();
+        }
+
+        // After 59 ticks the mouse is still alive
+        assert_eq!(1, world.mice.len());
+
+        // The 90th tick kills the mouse as it falls into the hole
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::Lose, world_state_change);
+        assert_eq!(0, world.mice.len());
+    }
+
+    /// GIVEN a world with a rocket one unit to the right of a cat
+    /// WHEN the cat walks into the rocket
+    /// THEN the cat is consumed
+    /// AND the rocket is destroyed
+    /// AND the world state changes to rocket destroyed
+    #[test]
+    fn cats_destroy_rockets_and_cause_loss() {
+        let mut world = World::new();
+
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..89 {
+            world.tick();
+        }
+
+        // After 89 ticks the cat is still alive
+        assert_eq!(1, world.cats.len());
+        assert!(world.get_rocket_events().is_empty());
+
+        // The 90th tick destroys the rocket as the cat enters it
+        let world_state_change = world.tick();
+
+        assert_eq!(0, world.cats.len());
+        assert_eq!(WorldStateChange::RocketDestroyed, world_state_change);
+        assert_eq!(TileType::RocketDestroyed, world.get_arrow(1, 0));
+        assert_eq!(
+            [RocketEvent {
+                x: 1,
+                y: 0,
+                kind: RocketEventKind::Destroyed
+            }],
+            world.get_rocket_events()
+        );
+
+        // The event only lasts for a single tick
+        world.tick();
+        assert!(world.get_rocket_events().is_empty());
+    }
+
+    /// GIVEN a world using the battle rocket penalty
+    /// AND a rocket one unit to the right of a cat
+    /// WHEN the cat walks into the rocket
+    /// THEN the cat is consumed
+    /// AND the rocket is marked as hit but survives
+    /// AND the world state does not change
+    #[test]
+    fn cats_hit_rockets_without_loss() {
+        let mut world = World::new();
+        world.set_rocket_penalty(RocketPenalty::Hit);
+
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..89 {
+            world.tick();
+        }
+
+        // The 90th tick hits the rocket as the cat enters it
+        let world_state_change = world.tick();
+
+        assert_eq!(0, world.cats.len());
+        assert_eq!(WorldStateChange::NoChange, world_state_change);
+        assert_eq!(TileType::Rocket, world.get_arrow(1, 0));
+        assert_eq!(
+            [RocketEvent {
+                x: 1,
+                y: 0,
+                kind: RocketEventKind::Hit
+            }],
+            world.get_rocket_events()
+        );
+    }
+
+    /// GIVEN a world with a destroyed rocket one unit to the right of a mouse
+    /// WHEN the mouse walks into the destroyed rocket
+    /// THEN the mouse is not rescued
+    #[test]
+    fn destroyed_rockets_do_not_rescue_mice() {
+        let mut world = World::new();
+
+        world.set_tile(1, 0, TileType::RocketDestroyed);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        for _ in 0..60 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+
+        assert_eq!(1, world.mice.len());
+        assert!(world.get_rocket_events().is_empty());
+    }
+
+    /// GIVEN a world with a rocket one unit to the right of a mouse
+    /// WHEN the mouse walks into the rocket
+    /// THEN the mouse is rescued
+    /// AND the world state changes to win
+    #[test]
+    fn rockets_rescue_mice_and_cause_win() {
+        let mut world = World::new();
+
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        for _ in 0..59 {
+            world.tick();
         }
 
-        // Left and right set
-        for y in 0..WORLD_HEIGHT {
-            assert_eq!(true, world.get_wall(0, y, Direction::Left));
-            assert_eq!(true, world.get_wall(WORLD_WIDTH - 1, y, Direction::Right));
+        // After 59 ticks the mouse is still alive
+        assert_eq!(1, world.mice.len());
+
+        // The 90th tick rescues the mouse as it falls into the hole
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::Win, world_state_change);
+        assert_eq!(0, world.mice.len());
+        assert_eq!(
+            [RocketEvent {
+                x: 1,
+                y: 0,
+                kind: RocketEventKind::Rescue
+            }],
+            world.get_rocket_events()
+        );
+    }
+
+    /// GIVEN a mouse and a cat walking towards each other in the same row
+    /// WHEN they come within half a square of each other
+    /// THEN the mouse is eaten
+    /// AND the world state changes to lose
+    #[test]
+    fn cats_eat_mice_head_on() {
+        let mut world = World::new();
+        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(6, 4, Direction::Left, WalkerType::Cat);
+        world.start();
+
+        // The walkers start four squares apart and close by 10/360 each tick
+        for _ in 0..125 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
 
-        // Everything else not set
-        for y in 1..WORLD_HEIGHT - 1 {
-            for x in 1..WORLD_WIDTH - 1 {
-                assert_eq!(false, world.get_wall(x, y, Direction::Up));
-                assert_eq!(false, world.get_wall(x, y, Direction::Down));
-                assert_eq!(false, world.get_wall(x, y, Direction::Left));
-                assert_eq!(false, world.get_wall(x, y, Direction::Right));
-            }
+        // After 125 ticks the mouse is still alive
+        assert_eq!(1, world.mice.len());
+
+        // The 126th tick brings them within half a square
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::Lose, world_state_change);
+        assert_eq!(0, world.mice.len());
+        assert_eq!(1, world.cats.len());
+    }
+
+    /// GIVEN a mouse following a cat in the same row
+    /// WHEN the mouse catches up to within a third of a square
+    /// THEN the mouse is eaten
+    /// AND the world state changes to lose
+    #[test]
+    fn cats_eat_mice_same_direction() {
+        let mut world = World::new();
+        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(4, 4, Direction::Right, WalkerType::Cat);
+        world.start();
+
+        // The walkers start two squares apart and close by 2/360 each tick
+        for _ in 0..299 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
+
+        // After 299 ticks the mouse is still alive
+        assert_eq!(1, world.mice.len());
+
+        // The 300th tick brings them within a third of a square
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::Lose, world_state_change);
+        assert_eq!(0, world.mice.len());
+        assert_eq!(1, world.cats.len());
     }
 
-    /// GIVEN an empty world
-    /// WHEN a walker is created
-    /// THEN the walker is added to the correct walker array
-    /// AND the walker is added to the backing data
+    /// GIVEN a mouse and a cat walking at right angles towards the same square
+    /// WHEN they come within a third of a square on both axes
+    /// THEN the mouse is eaten
+    /// AND the world state changes to lose
     #[test]
-    fn walker_creation() {
+    fn cats_eat_mice_at_right_angles() {
         let mut world = World::new();
-        world.create_walker(1, 1, Direction::Down, WalkerType::Mouse);
-        world.create_walker(4, 4, Direction::Up, WalkerType::Cat);
+        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(5, 2, Direction::Down, WalkerType::Cat);
+        world.start();
 
-        // The walkers exist
+        // Both walkers reach (5, 4) after 180 ticks. The mouse is within a third of a square
+        // after 160 ticks, by which point the cat is already close enough
+        for _ in 0..159 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+
+        // After 159 ticks the mouse is still alive
         assert_eq!(1, world.mice.len());
+
+        // The 160th tick brings them within a third of a square on both axes
+        let world_state_change = world.tick();
+
+        assert_eq!(WorldStateChange::Lose, world_state_change);
+        assert_eq!(0, world.mice.len());
         assert_eq!(1, world.cats.len());
+    }
 
-        // The source data has been updated to include the walker
-        let walker_data = &world.data[HEADER_SIZE + WALL_BLOCK_SIZE..];
-        // Walkers are packed into one byte, so let's find them
-        assert_eq!(ENTITY_TYPE_MOUSE, walker_data[WORLD_WIDTH * 1 + 1] & ENTITY_TYPE_MASK);
-        assert_eq!(ENTITY_DIRECTION_DOWN, walker_data[WORLD_WIDTH * 1 + 1] & ENTITY_DIRECTION_MASK);
-        assert_eq!(ENTITY_TYPE_CAT, walker_data[WORLD_WIDTH * 4 + 4] & ENTITY_TYPE_MASK);
-        assert_eq!(ENTITY_DIRECTION_UP, walker_data[WORLD_WIDTH * 4 + 4] & ENTITY_DIRECTION_MASK);
+    /// GIVEN a mouse and a cat walking towards each other in adjacent rows
+    /// WHEN they pass each other
+    /// THEN the mouse is not eaten
+    #[test]
+    fn cats_do_not_eat_mice_in_adjacent_rows() {
+        let mut world = World::new();
+        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(6, 5, Direction::Left, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..300 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+
+        assert_eq!(1, world.mice.len());
+        assert_eq!(1, world.cats.len());
     }
 
-    /// GIVEN an existing walker
-    /// WHEN a new walker is created at the same spot
-    /// THEN the operation is rejected
+    /// GIVEN a serialised world containing walkers
+    /// WHEN it is loaded
+    /// THEN the walkers are spawned in the correct positions
     #[test]
-    fn walkers_cannot_be_created_in_same_square() {
+    fn load_spawns_walkers() {
+        let mut source = World::new();
+        source.create_walker(1, 2, Direction::Down, WalkerType::Mouse);
+        source.create_walker(3, 4, Direction::Left, WalkerType::Cat);
+
+        let world = World::load(source.data);
+
+        assert_eq!(1, world.mice.len());
+        assert_eq!(1, world.cats.len());
+        assert_eq!(1, world.mice[0].get_x().integer_part());
+        assert_eq!(2, world.mice[0].get_y().integer_part());
+        assert_eq!(Direction::Down, world.mice[0].get_direction());
+        assert_eq!(3, world.cats[0].get_x().integer_part());
+        assert_eq!(4, world.cats[0].get_y().integer_part());
+        assert_eq!(Direction::Left, world.cats[0].get_direction());
+    }
+
+    /// GIVEN a mouse part way across a square
+    /// WHEN mice are sped up to a speed that does not divide the rest of the square
+    /// THEN the mouse still stops on the centre of each square, and reaches the rocket sooner
+    #[test]
+    fn walker_speed_change_keeps_alignment() {
         let mut world = World::new();
-        let created_1 = world.create_walker(0, 0, Direction::Down, WalkerType::Cat);
-        let created_2 = world.create_walker(0, 0, Direction::Down, WalkerType::Cat);
+        world.set_tile(2, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
+        for _ in 0..5 {
+            world.tick();
+        }
 
-        assert_eq!(true, created_1);
-        assert_eq!(false, created_2);
+        world.set_walker_speed(WalkerType::Mouse, FixedPoint::new(0, 12));
+        for _ in 0..28 {
+            world.tick();
+        }
+        assert_eq!(FixedPoint::new(1, 180), world.mice[0].get_x());
+
+        assert_eq!(29, ticks_to_win(&mut world));
     }
 
-    /// GIVEN a wall directly ahead
-    /// WHEN a walker walks towards/along/away from the wall
-    /// THEN the correct turns (right/none/none) are made
+    /// GIVEN a mouse walking towards an arrow
+    /// WHEN it reaches the arrow's square
+    /// THEN it turns at the centre of the square rather than at its edge
     #[test]
-    fn walker_wall_straight() {
-        let world = World::new();
-        let mut walker_up = Walker::new(4, 0, Direction::Up, WalkerType::Mouse);
-        let mut walker_down = Walker::new(4, 0, Direction::Down, WalkerType::Mouse);
-        let mut walker_left = Walker::new(4, 0, Direction::Left, WalkerType::Mouse);
-        let mut walker_right = Walker::new(4, 0, Direction::Right, WalkerType::Mouse);
+    fn walkers_turn_at_square_centres() {
+        let mut world = World::new();
+        world.set_arrow(4, 2, TileType::Down);
+        world.create_walker(5, 2, Direction::Left, WalkerType::Mouse);
+        assert_eq!(FixedPoint::new(5, 180), world.mice[0].get_x());
+        world.start();
+
+        for _ in 0..59 {
+            world.tick();
+        }
+        assert_eq!(Direction::Left, world.mice[0].get_direction());
 
-        World::check_walls(&world.data, &mut walker_up);
-        World::check_walls(&world.data, &mut walker_down);
-        World::check_walls(&world.data, &mut walker_left);
-        World::check_walls(&world.data, &mut walker_right);
+        world.tick();
+        assert_eq!(Direction::Down, world.mice[0].get_direction());
 
-        assert_eq!(Direction::Right, walker_up.get_direction());
-        assert_eq!(Direction::Down, walker_down.get_direction());
-        assert_eq!(Direction::Left, walker_left.get_direction());
-        assert_eq!(Direction::Right, walker_right.get_direction());
+        for _ in 0..30 {
+            world.tick();
+        }
+        assert_eq!(FixedPoint::new(4, 180), world.mice[0].get_x());
+        assert_eq!(FixedPoint::new(3, 0), world.mice[0].get_y());
     }
 
-    /// GIVEN a wall directly ahead and to the right
-    /// WHEN the walker walks towards/left/right/away from the wall
-    /// THEN the correct turns (left/none/down/none) are made
+    /// GIVEN a puzzle with a mouse spawner and a cat spawner
+    /// WHEN it is loaded
+    /// THEN both spawners are created with their directions, and no walkers are spawned yet
     #[test]
-    fn walker_wall_forced_left() {
-        let world = World::new();
-        let mut walker_up = Walker::new(11, 0, Direction::Up, WalkerType::Mouse);
-        let mut walker_down = Walker::new(11, 0, Direction::Down, WalkerType::Mouse);
-        let mut walker_left = Walker::new(11, 0, Direction::Left, WalkerType::Mouse);
-        let mut walker_right = Walker::new(11, 0, Direction::Right, WalkerType::Mouse);
+    fn load_creates_spawners() {
+        let data = world_macros::puzzle!("Spawners", "Test",
+        "┌────────────────────────┐"
+        "│S>                      │"
+        "│                        │"
+        "│                        │"
+        "│                        │"
+        "│          K^            │"
+        "└────────────────────────┘");
+        let world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        let spawners = world.get_spawners();
+        assert_eq!(2, spawners.len());
+        assert_eq!((0, 0), (spawners[0].get_x(), spawners[0].get_y()));
+        assert_eq!(Direction::Right, spawners[0].get_direction());
+        assert_eq!(WalkerType::Mouse, spawners[0].get_type());
+        assert_eq!((2, 2), (spawners[1].get_x(), spawners[1].get_y()));
+        assert_eq!(Direction::Up, spawners[1].get_direction());
+        assert_eq!(WalkerType::Cat, spawners[1].get_type());
+        assert!(world.mice.is_empty());
+        assert!(world.cats.is_empty());
+        assert_eq!(data, world.to_bytes());
+    }
+
+    /// GIVEN a spawner with an interval of 10 ticks
+    /// WHEN the world is run, then reset
+    /// THEN a walker is spawned every 10 ticks in the spawner direction, and resetting restarts
+    /// the countdown
+    #[test]
+    fn spawners_emit_walkers_at_interval() {
+        let mut world = World::new();
+        world.create_spawner(3, 3, Direction::Down, WalkerType::Cat);
+        assert!(world.set_spawn_interval(3, 3, 10));
+        world.start();
 
-        World::check_walls(&world.data, &mut walker_up);
-        World::check_walls(&world.data, &mut walker_down);
-        World::check_walls(&world.data, &mut walker_left);
-        World::check_walls(&world.data, &mut walker_right);
+        for _ in 0..9 {
+            world.tick();
+        }
+        assert!(world.cats.is_empty());
 
-        assert_eq!(Direction::Left, walker_up.get_direction());
-        assert_eq!(Direction::Down, walker_down.get_direction());
-        assert_eq!(Direction::Left, walker_left.get_direction());
-        assert_eq!(Direction::Down, walker_right.get_direction());
+        world.tick();
+        assert_eq!(1, world.cats.len());
+        assert_eq!(Direction::Down, world.cats[0].get_direction());
+        assert_eq!(3, world.cats[0].get_y().integer_part());
+
+        for _ in 0..10 {
+            world.tick();
+        }
+        assert_eq!(2, world.cats.len());
+
+        world.tick();
+        world.reset();
+        assert!(world.cats.is_empty());
+        assert_eq!(10, world.get_spawners()[0].get_countdown());
     }
 
-    /// GIVEN a wall in a U shape (directly ahead and to the left and right)
-    /// WHEN the walker walks towards/left/right/away from the wall
-    /// THEN the correct turns (around/left/right/none) are made
+    /// GIVEN three cats, the middle one walking into a hole, and a spawner
+    /// WHEN the middle cat dies and the spawner creates a cat
+    /// THEN the other cats keep their IDs, the dead cat is no longer found and the spawned cat
+    /// gets a new ID
     #[test]
-    fn walker_wall_u_shape() {
+    fn walker_ids_are_stable() {
         let mut world = World::new();
-        world.set_wall(0, 0, Direction::Right, true);
-        let mut walker_up = Walker::new(0, 0, Direction::Up, WalkerType::Mouse);
-        let mut walker_down = Walker::new(0, 0, Direction::Down, WalkerType::Mouse);
-        let mut walker_left = Walker::new(0, 0, Direction::Left, WalkerType::Mouse);
-        let mut walker_right = Walker::new(0, 0, Direction::Right, WalkerType::Mouse);
+        world.set_tile(1, 2, TileType::Hole);
+        for y in 1..=3 {
+            world.create_walker(0, y, Direction::Right, WalkerType::Cat);
+        }
+        world.create_spawner(5, 5, Direction::Down, WalkerType::Cat);
+        world.start();
 
-        World::check_walls(&world.data, &mut walker_up);
-        World::check_walls(&world.data, &mut walker_down);
-        World::check_walls(&world.data, &mut walker_left);
-        World::check_walls(&world.data, &mut walker_right);
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+        for _ in 0..90 {
+            world.tick_with_events(&mut events);
+        }
 
-        assert_eq!(Direction::Down, walker_up.get_direction());
-        assert_eq!(Direction::Down, walker_down.get_direction());
-        assert_eq!(Direction::Down, walker_left.get_direction());
-        assert_eq!(Direction::Down, walker_right.get_direction());
+        assert!(world.get_walker(WalkerId::new(1)).is_none());
+        assert_eq!(
+            1,
+            world
+                .get_walker(WalkerId::new(0))
+                .unwrap()
+                .get_y()
+                .integer_part()
+        );
+        assert_eq!(
+            3,
+            world
+                .get_walker(WalkerId::new(2))
+                .unwrap()
+                .get_y()
+                .integer_part()
+        );
+        assert_eq!(
+            5,
+            world
+                .get_walker(WalkerId::new(3))
+                .unwrap()
+                .get_x()
+                .integer_part()
+        );
+        assert_eq!(
+            [
+                WorldEvent::Spawned {
+                    id: WalkerId::new(3),
+                    x: 5,
+                    y: 5,
+                    walker_type: WalkerType::Cat,
+                    direction: Direction::Down
+                },
+                WorldEvent::Died {
+                    id: WalkerId::new(1),
+                    x: 1,
+                    y: 2,
+                    walker_type: WalkerType::Cat,
+                    cause: DeathCause::Hole
+                }
+            ],
+            events.as_slice()
+        );
     }
 
-    /// GIVEN an arrow with no nearby walls
-    /// WHEN a walker encounters that arrow at right angles
-    /// THEN the walker turns in that direction
+    /// GIVEN a mouse walking to a rocket, a mouse walking along an empty row and a cat walking to
+    /// a hole
+    /// WHEN the rescue and the death have happened
+    /// THEN the counts of alive, rescued and dead walkers reflect them, until the world is reset
     #[test]
-    fn walker_arrow_right_angle_turns() {
+    fn walker_counts_track_removed_walkers() {
         let mut world = World::new();
-        world.set_arrow(2, 2, TileType::Up);
-        world.set_arrow(4, 3, TileType::Down);
-        world.set_arrow(6, 4, TileType::Left);
-        world.set_arrow(8, 5, TileType::Right);
+        world.set_tile(2, 0, TileType::Rocket);
+        world.set_tile(1, 2, TileType::Hole);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 4, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 2, Direction::Right, WalkerType::Cat);
+        world.start();
 
-        // Walkers approaching up arrow from left/right
-        world.create_walker(1, 2, Direction::Right, WalkerType::Mouse);
-        world.create_walker(3, 2, Direction::Left, WalkerType::Mouse);
+        for _ in 0..120 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
 
-        // Walkers approaching down arrow from left/right
-        world.create_walker(3, 3, Direction::Right, WalkerType::Mouse);
-        world.create_walker(5, 3, Direction::Left, WalkerType::Mouse);
+        assert_eq!(
+            WalkerCounts {
+                alive: 1,
+                rescued: 1,
+                dead: 0
+            },
+            world.get_walker_counts(WalkerType::Mouse)
+        );
+        assert_eq!(
+            WalkerCounts {
+                alive: 0,
+                rescued: 0,
+                dead: 1
+            },
+            world.get_walker_counts(WalkerType::Cat)
+        );
+
+        world.reset();
+        assert_eq!(
+            WalkerCounts {
+                alive: 2,
+                rescued: 0,
+                dead: 0
+            },
+            world.get_walker_counts(WalkerType::Mouse)
+        );
+    }
 
-        // Walkers approaching left arrow from top/bottom
-        world.create_walker(6, 3, Direction::Down, WalkerType::Mouse);
-        world.create_walker(6, 5, Direction::Up, WalkerType::Mouse);
+    /// GIVEN a small world with a wall and a rocket
+    /// WHEN the tiles and walls are listed
+    /// THEN every square is listed once, and each wall is listed once as a top or left wall
+    #[test]
+    fn tile_and_wall_views() {
+        let mut world = World::<4, 3, 12, { world_data_size(4, 3) }>::new_sized();
+        world.set_wall(1, 1, Direction::Right, true);
+        world.set_tile(3, 2, TileType::Rocket);
+
+        assert_eq!((4, 3), (world.get_width(), world.get_height()));
+        assert_eq!(12, world.get_tiles().count());
+        assert_eq!(
+            Some((3, 2, TileType::Rocket)),
+            world
+                .get_tiles()
+                .find(|&(_, _, tile)| tile != TileType::Empty)
+        );
+
+        assert_eq!(4 + 3 + 1, world.get_walls().count());
+        assert!(world
+            .get_walls()
+            .any(|wall| wall == (2, 1, Direction::Left)));
+        assert!(world
+            .get_walls()
+            .all(|(x, y, direction)| world.get_wall(x, y, direction)));
+    }
 
-        // Walkers approaching right arrow from top/bottom
-        world.create_walker(8, 4, Direction::Down, WalkerType::Mouse);
-        world.create_walker(8, 6, Direction::Up, WalkerType::Mouse);
+    /// GIVEN a spawner creating a mouse every tick in a small, closed world
+    /// WHEN the world is run for longer than it takes to fill
+    /// THEN spawning stops at MAX_WALKERS mice
+    #[test]
+    fn spawners_stop_at_max_walkers() {
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::new_sized();
+        world.create_spawner(0, 0, Direction::Right, WalkerType::Mouse);
+        world.set_spawn_interval(0, 0, 1);
+        world.start();
+
+        for _ in 0..100 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
 
-        // Run for the time is takes for a mouse to move just before one square
-        for _ in 0..59 {
+        assert_eq!(World::<5, 3, 15, { world_data_size(5, 3) }>::MAX_WALKERS, world.mice.len());
+    }
+
+    /// GIVEN a world where walkers have moved, been removed and changed direction
+    /// WHEN the world is reset
+    /// THEN every walker is respawned at its starting position and direction
+    #[test]
+    fn reset_respawns_walkers() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Hole);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        world.create_walker(4, 4, Direction::Up, WalkerType::Mouse);
+        world.start();
+
+        // The cat falls into the hole and the mouse walks up
+        for _ in 0..90 {
             world.tick();
         }
+        assert_eq!(0, world.cats.len());
+        assert_eq!(FixedPoint::new(3, 0), world.mice[0].get_y());
 
-        // Walkers should not have changed their directions yet
-        assert_eq!(Direction::Right, world.mice[0].get_direction());
-        assert_eq!(Direction::Left, world.mice[1].get_direction());
-        assert_eq!(Direction::Right, world.mice[2].get_direction());
-        assert_eq!(Direction::Left, world.mice[3].get_direction());
-        assert_eq!(Direction::Down, world.mice[4].get_direction());
-        assert_eq!(Direction::Up, world.mice[5].get_direction());
-        assert_eq!(Direction::Down, world.mice[6].get_direction());
-        assert_eq!(Direction::Up, world.mice[7].get_direction());
+        world.reset();
+
+        assert_eq!(1, world.mice.len());
+        assert_eq!(1, world.cats.len());
+        assert_eq!(0, world.cats[0].get_x().integer_part());
+        assert_eq!(Direction::Right, world.cats[0].get_direction());
+        assert_eq!(4, world.mice[0].get_y().integer_part());
+        assert_eq!(Direction::Up, world.mice[0].get_direction());
+    }
+
+    /// GIVEN a world where cats have diminished one arrow, removed another and destroyed a rocket
+    /// WHEN the world is reset
+    /// THEN the arrows are restored to full size
+    /// AND the rocket is restored
+    #[test]
+    fn reset_restores_arrows_and_rockets() {
+        let mut world = World::new();
+        world.set_arrow(4, 4, TileType::Down);
+        world.set_arrow(6, 4, TileType::Down);
+        world.set_arrow(8, 4, TileType::Right);
+        world.set_tile(9, 4, TileType::Rocket);
+        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.create_walker(6, 5, Direction::Up, WalkerType::Cat);
+        world.create_walker(6, 6, Direction::Up, WalkerType::Cat);
+        world.create_walker(8, 5, Direction::Up, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..180 {
+            world.tick();
+        }
+
+        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
+        assert_eq!(TileType::Empty, world.get_arrow(6, 4));
+        assert_eq!(TileType::RocketDestroyed, world.get_arrow(9, 4));
+
+        world.reset();
+
+        assert_eq!(TileType::Down, world.get_arrow(4, 4));
+        assert_eq!(TileType::Down, world.get_arrow(6, 4));
+        assert_eq!(TileType::Right, world.get_arrow(8, 4));
+        assert_eq!(TileType::Rocket, world.get_arrow(9, 4));
+        assert_eq!(4, world.cats.len());
+    }
+
+    /// GIVEN a world with one up arrow in stock
+    /// WHEN an up arrow is placed
+    /// THEN the arrow is set
+    /// AND the stock is reduced
+    /// AND a second up arrow cannot be placed
+    #[test]
+    fn place_arrow_takes_from_stock() {
+        let mut world = World::new();
+        world.arrow_stock[Direction::Up] = 1;
+
+        assert_eq!(Ok(()), world.place_arrow(2, 3, Direction::Up));
+        assert_eq!(TileType::Up, world.get_arrow(2, 3));
+        assert_eq!(0, world.get_arrow_stock()[Direction::Up]);
+
+        assert_eq!(Err(PlacementError::OutOfStock), world.place_arrow(4, 3, Direction::Up));
+        assert_eq!(TileType::Empty, world.get_arrow(4, 3));
+    }
+
+    /// GIVEN a world with arrows in stock, a rocket and a hole
+    /// WHEN arrows are placed on the rocket and hole
+    /// THEN the placements are refused
+    /// AND the stock is unchanged
+    #[test]
+    fn place_arrow_refuses_rockets_and_holes() {
+        let mut world = World::new();
+        world.arrow_stock[Direction::Left] = 2;
+        world.set_tile(1, 1, TileType::Rocket);
+        world.set_tile(2, 2, TileType::Hole);
+
+        assert_eq!(Err(PlacementError::TileOccupied), world.place_arrow(1, 1, Direction::Left));
+        assert_eq!(Err(PlacementError::TileOccupied), world.place_arrow(2, 2, Direction::Left));
+        assert_eq!(TileType::Rocket, world.get_arrow(1, 1));
+        assert_eq!(TileType::Hole, world.get_arrow(2, 2));
+        assert_eq!(2, world.get_arrow_stock()[Direction::Left]);
+    }
+
+    /// GIVEN a placed up arrow and a right arrow in stock
+    /// WHEN a right arrow is placed over the up arrow
+    /// THEN the arrows are swapped in the stock
+    /// AND WHEN placed again in the same direction
+    /// THEN nothing changes
+    #[test]
+    fn place_arrow_swaps_existing_arrow() {
+        let mut world = World::new();
+        world.arrow_stock[Direction::Up] = 1;
+        world.arrow_stock[Direction::Right] = 1;
+        world.place_arrow(5, 5, Direction::Up).unwrap();
+
+        assert_eq!(Ok(()), world.place_arrow(5, 5, Direction::Right));
+        assert_eq!(TileType::Right, world.get_arrow(5, 5));
+        assert_eq!(1, world.get_arrow_stock()[Direction::Up]);
+        assert_eq!(0, world.get_arrow_stock()[Direction::Right]);
+
+        assert_eq!(Ok(()), world.place_arrow(5, 5, Direction::Right));
+        assert_eq!(TileType::Right, world.get_arrow(5, 5));
+        assert_eq!(1, world.get_arrow_stock()[Direction::Up]);
+        assert_eq!(0, world.get_arrow_stock()[Direction::Right]);
+    }
+
+    /// GIVEN a placed arrow
+    /// WHEN it is removed
+    /// THEN it is returned to the stock
+    /// AND removing it again fails
+    #[test]
+    fn remove_arrow_returns_to_stock() {
+        let mut world = World::new();
+        world.arrow_stock[Direction::Down] = 1;
+        world.place_arrow(7, 2, Direction::Down).unwrap();
+
+        assert_eq!(Ok(Direction::Down), world.remove_arrow(7, 2));
+        assert_eq!(TileType::Empty, world.get_arrow(7, 2));
+        assert_eq!(1, world.get_arrow_stock()[Direction::Down]);
+
+        assert_eq!(Err(PlacementError::NoArrow), world.remove_arrow(7, 2));
+        assert_eq!(1, world.get_arrow_stock()[Direction::Down]);
+    }
+
+    /// GIVEN a loaded puzzle with solution arrows
+    /// WHEN the arrows are placed from the stock
+    /// THEN each solution arrow can be placed exactly once
+    #[test]
+    fn loaded_stock_can_be_placed() {
+        let mut source = World::new();
+        let tile_block =
+            &mut source.data[<World>::TILE_BLOCK_OFFSET..<World>::TILE_BLOCK_OFFSET + MAX_TILES];
+        tile_block[0] = ARROW_PRESENT_MASK | ARROW_DIRECTION_LEFT;
+        let mut world = World::load(source.data);
+
+        assert_eq!(1, world.get_arrow_stock()[Direction::Left]);
+        assert_eq!(Ok(()), world.place_arrow(3, 3, Direction::Left));
+        assert_eq!(Err(PlacementError::OutOfStock), world.place_arrow(4, 4, Direction::Left));
+    }
+
+    /// GIVEN a newly created world with a mouse
+    /// WHEN it is ticked
+    /// THEN the world is stopped and the mouse does not move
+    #[test]
+    fn stopped_world_does_not_advance() {
+        let mut world = World::new();
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
 
-        // Tick the final time required for mice to walk one square
-        world.tick();
+        assert_eq!(WorldState::Stopped, world.get_state());
+        for _ in 0..60 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
 
-        // Walkers should now have been directed by the arrows
-        assert_eq!(Direction::Up, world.mice[0].get_direction());
-        assert_eq!(Direction::Up, world.mice[1].get_direction());
-        assert_eq!(Direction::Down, world.mice[2].get_direction());
-        assert_eq!(Direction::Down, world.mice[3].get_direction());
-        assert_eq!(Direction::Left, world.mice[4].get_direction());
-        assert_eq!(Direction::Left, world.mice[5].get_direction());
-        assert_eq!(Direction::Right, world.mice[6].get_direction());
-        assert_eq!(Direction::Right, world.mice[7].get_direction());
+        assert_eq!(0, world.mice[0].get_x().integer_part());
     }
 
-    /// GIVEN an arrow with no nearby walls
-    /// WHEN a mouse encounters an arrow in opposite direction
-    /// THEN the mouse is turned around
-    /// AND the arrow is unchanged
+    /// GIVEN a world with a mouse one square from a rocket
+    /// WHEN it is run fast
+    /// THEN each tick advances several simulation steps
+    /// AND the mouse reaches the rocket in a fraction of the ticks
     #[test]
-    fn mice_do_not_diminish_arrows_if_opposed() {
+    fn fast_forward_runs_multiple_steps() {
         let mut world = World::new();
-        world.set_arrow(4, 3, TileType::Up);
-        world.set_arrow(4, 5, TileType::Down);
-        world.set_arrow(3, 4, TileType::Left);
-        world.set_arrow(5, 4, TileType::Right);
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.fast_forward();
 
-        world.create_walker(4, 2, Direction::Down, WalkerType::Mouse);
-        world.create_walker(4, 6, Direction::Up, WalkerType::Mouse);
-        world.create_walker(2, 4, Direction::Right, WalkerType::Mouse);
-        world.create_walker(6, 4, Direction::Left, WalkerType::Mouse);
+        for _ in 0..(60 / FAST_FORWARD_STEPS) - 1 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+
+        assert_eq!(WorldStateChange::Win, world.tick());
+        assert_eq!(WorldState::Success, world.get_state());
+    }
+
+    /// GIVEN a world that is won once a single mouse is rescued
+    /// WHEN one of two mice enters a rocket
+    /// THEN the world is won while the other mouse is still walking
+    #[test]
+    fn win_condition_mice_rescued() {
+        let mut world = World::new();
+        world.set_ruleset(Ruleset {
+            win_condition: WinCondition::MiceRescued(1),
+            ..Ruleset::puzzle()
+        });
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 4, Direction::Right, WalkerType::Mouse);
+        world.start();
 
-        // Run for the time is takes for a mouse to move just before one square
         for _ in 0..59 {
-            world.tick();
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
 
-        // The walkers have not yet encountered the arrows and have their original direction
-        assert_eq!(Direction::Down, world.mice[0].get_direction());
-        assert_eq!(Direction::Up, world.mice[1].get_direction());
-        assert_eq!(Direction::Right, world.mice[2].get_direction());
-        assert_eq!(Direction::Left, world.mice[3].get_direction());
+        assert_eq!(WorldStateChange::Win, world.tick());
+        assert_eq!(1, world.get_mice().len());
+    }
 
-        // Tick the final time required for mice to walk one square
-        world.tick();
+    /// GIVEN a world that is lost once two mice die
+    /// WHEN two mice fall into holes a square apart
+    /// THEN the first death is survived and the second loses
+    #[test]
+    fn loss_condition_mice_die() {
+        let mut world = World::new();
+        world.set_ruleset(Ruleset {
+            loss_condition: LossCondition::MiceDie(2),
+            ..Ruleset::puzzle()
+        });
+        world.set_tile(1, 0, TileType::Hole);
+        world.set_tile(2, 4, TileType::Hole);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 4, Direction::Right, WalkerType::Mouse);
+        world.start();
 
-        // Walkers should now have been directed by the arrows
-        assert_eq!(Direction::Up, world.mice[0].get_direction());
-        assert_eq!(Direction::Down, world.mice[1].get_direction());
-        assert_eq!(Direction::Left, world.mice[2].get_direction());
-        assert_eq!(Direction::Right, world.mice[3].get_direction());
+        for _ in 0..119 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
 
-        // The arrows are unchanged.
-        assert_eq!(TileType::Up, world.get_arrow(4, 3));
-        assert_eq!(TileType::Down, world.get_arrow(4, 5));
-        assert_eq!(TileType::Left, world.get_arrow(3, 4));
-        assert_eq!(TileType::Right, world.get_arrow(5, 4));
+        assert_eq!(WorldStateChange::Lose, world.tick());
+        assert_eq!(2, world.get_walker_counts(WalkerType::Mouse).dead);
     }
 
-    /// GIVEN an arrow with no nearby walls
-    /// WHEN a cat encounters an arrow in opposite direction
-    /// THEN the cat is turned around
-    /// AND the arrow is diminished
+    /// GIVEN a world with the battle rules
+    /// WHEN one mouse falls into a hole and another enters a rocket
+    /// THEN the world is neither won nor lost
     #[test]
-    fn cats_diminish_arrows_if_opposed() {
+    fn battle_ruleset_never_wins_or_loses() {
         let mut world = World::new();
-        world.set_arrow(4, 4, TileType::Down);
-        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
+        world.set_ruleset(Ruleset::battle());
+        world.set_tile(1, 0, TileType::Hole);
+        world.set_tile(2, 4, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(0, 4, Direction::Right, WalkerType::Mouse);
+        world.start();
 
-        // Walk cat to edge of arrow. Cats move at 2/3 speed of a mouse, so 90 ticks required
-        for _ in 0..89 {
-            world.tick();
+        for _ in 0..180 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
 
-        // The arrow and cat are unchanged
-        assert_eq!(TileType::Down, world.get_arrow(4, 4));
-        assert_eq!(Direction::Up, world.cats[0].get_direction());
+        assert_eq!(WorldState::Running, world.get_state());
+    }
 
-        // Tick the final time required for cats to walk one square
-        world.tick();
+    /// GIVEN a mouse bouncing between two arrows that face each other
+    /// WHEN the world is run
+    /// THEN the loop is reported as a stalemate once the mouse returns to a square it was seen in
+    /// AND the world is defeated
+    #[test]
+    fn looping_walkers_stalemate() {
+        let mut world = World::new();
+        world.set_arrow(2, 2, TileType::Right);
+        world.set_arrow(3, 2, TileType::Left);
+        world.create_walker(2, 2, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        let mut ticks = 1;
+        while world.tick() == WorldStateChange::NoChange {
+            ticks += 1;
+            assert!(ticks < 1000);
+        }
 
-        // The arrow is diminished and the cat turned around
-        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
-        assert_eq!(Direction::Down, world.cats[0].get_direction());
+        // The configuration after the first square is seen again two squares later
+        assert_eq!(180, ticks);
+        assert_eq!(WorldState::Defeat, world.get_state());
+        assert_eq!(1, world.get_mice().len());
     }
 
-    /// GIVEN an arrow with no nearby walls
-    /// WHEN three cats encounter an arrow in opposite direction
-    /// THEN the first two cats are turned around
-    /// AND the last two cats continues
-    /// AND the arrow is removed
+    /// GIVEN a list of rocket events that is already full
+    /// WHEN a cat enters a rocket
+    /// THEN the rocket is still destroyed and reported to the event sink, without panicking
     #[test]
-    fn double_diminish_removes_arrow() {
-        let mut world = World::new();
-        world.set_arrow(4, 4, TileType::Down);
-        world.create_walker(4, 5, Direction::Up, WalkerType::Cat);
-        world.create_walker(4, 6, Direction::Up, WalkerType::Cat);
-        world.create_walker(4, 7, Direction::Up, WalkerType::Cat);
+    fn rocket_events_full() {
+        let mut tiles = [TileType::Empty; MAX_TILES];
+        tiles[1] = TileType::Rocket;
+        let mut rocket_events: ArrayVec<RocketEvent, MAX_TILES> = ArrayVec::new();
+        while !rocket_events.is_full() {
+            rocket_events.push(RocketEvent {
+                x: 5,
+                y: 5,
+                kind: RocketEventKind::Rescue,
+            });
+        }
+        let mut cat = Walker::new(WalkerId::new(0), 1, 0, Direction::Right, WalkerType::Cat);
+        let mut events: ArrayVec<WorldEvent, 4> = ArrayVec::new();
+
+        let destroyed = <World>::check_rockets_and_holes(
+            &mut tiles,
+            RocketPenalty::Destroy,
+            &mut rocket_events,
+            &mut cat,
+            &mut events,
+        );
+
+        assert!(destroyed);
+        assert_eq!(TileType::RocketDestroyed, tiles[1]);
+        assert!(rocket_events
+            .iter()
+            .all(|event| event.kind == RocketEventKind::Rescue));
+        assert_eq!(
+            [WorldEvent::Rocket {
+                id: WalkerId::new(0),
+                event: RocketEvent {
+                    x: 1,
+                    y: 0,
+                    kind: RocketEventKind::Destroyed
+                }
+            }],
+            events.as_slice()
+        );
+    }
 
-        // Walk first cat into the arrow
-        for _ in 0..90 {
-            world.tick();
+    /// GIVEN a mouse walking round an open row, whose configuration on reaching the third
+    /// square has been stored to spot loops
+    /// WHEN a wall is added across the row ahead of the fourth square
+    /// THEN no stalemate is reported as the mouse returns to the third square, and the mouse
+    /// turns at the wall
+    #[test]
+    fn wall_edit_resets_stalemate() {
+        let data = world_macros::puzzle!("Wall edit", "Test",
+        "┌────────────────────────┐"
+        "│                        │"
+        "                          "
+        " M>                       "
+        "                          "
+        "│                        │"
+        "└────────────────────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+        world.start();
+
+        // The configuration is stored after 7 squares, and would be seen again after 12
+        for _ in 0..480 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+        world.set_wall(3, 1, Direction::Right, true);
+        for _ in 480..840 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
 
-        // The arrow is diminished and first cat turned around
-        assert_eq!(TileType::DownHalf, world.get_arrow(4, 4));
-        assert_eq!(Direction::Down, world.cats[0].get_direction());
-        assert_eq!(Direction::Up, world.cats[1].get_direction());
-        assert_eq!(Direction::Up, world.cats[2].get_direction());
+        // The mouse turned down at the wall, and has reached the bottom row
+        assert_eq!(2, world.get_mice()[0].get_y().floor());
+    }
 
-        // Walk second cat into the arrow
-        for _ in 0..90 {
+    /// GIVEN a mouse bouncing between two arrows that face each other
+    /// WHEN the random number generator is drawn from between ticks
+    /// THEN no stalemate is reported, as the configuration never repeats
+    #[test]
+    fn rng_prevents_stalemate() {
+        let mut world = World::new();
+        world.set_arrow(2, 2, TileType::Right);
+        world.set_arrow(3, 2, TileType::Left);
+        world.create_walker(2, 2, Direction::Right, WalkerType::Mouse);
+        world.start();
+
+        for _ in 0..1000 {
+            world.get_rng_mut().next_u32();
+            assert_eq!(WorldStateChange::NoChange, world.tick());
+        }
+        assert_eq!(WorldState::Running, world.get_state());
+    }
+
+    /// GIVEN a world that has been won
+    /// WHEN it is ticked again or started
+    /// THEN the success state is kept
+    #[test]
+    fn success_latches() {
+        let mut world = World::new();
+        world.set_tile(1, 0, TileType::Rocket);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.create_walker(5, 5, Direction::Right, WalkerType::Cat);
+        world.start();
+
+        for _ in 0..60 {
             world.tick();
         }
+        assert_eq!(WorldState::Success, world.get_state());
+
+        // The cat no longer moves
+        let cat_x = world.cats[0].get_x();
+        assert_eq!(WorldStateChange::NoChange, world.tick());
+        world.start();
+        world.fast_forward();
+        assert_eq!(WorldStateChange::NoChange, world.tick());
+        assert_eq!(WorldState::Success, world.get_state());
+        assert!(cat_x == world.cats[0].get_x());
+    }
 
-        // The arrow is removed and the second cat turned around
-        assert_eq!(TileType::Empty, world.get_arrow(4, 4));
-        assert_eq!(Direction::Down, world.cats[0].get_direction());
-        assert_eq!(Direction::Down, world.cats[1].get_direction());
-        assert_eq!(Direction::Up, world.cats[2].get_direction());
+    /// GIVEN worlds where a mouse is lost or a rocket destroyed
+    /// WHEN they are ticked until the loss
+    /// THEN the defeat state is latched
+    #[test]
+    fn defeat_latches() {
+        let mut mouse_lost = World::new();
+        mouse_lost.set_tile(1, 0, TileType::Hole);
+        mouse_lost.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        mouse_lost.start();
+
+        let mut rocket_lost = World::new();
+        rocket_lost.set_tile(1, 0, TileType::Rocket);
+        rocket_lost.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        rocket_lost.start();
 
-        // Walk third cat into the 'arrow'
         for _ in 0..90 {
-            world.tick();
+            mouse_lost.tick();
+            rocket_lost.tick();
         }
 
-        // The arrow is still gone and the cat continues on his way
-        assert_eq!(TileType::Empty, world.get_arrow(4, 4));
-        assert_eq!(Direction::Down, world.cats[0].get_direction());
-        assert_eq!(Direction::Down, world.cats[1].get_direction());
-        assert_eq!(Direction::Up, world.cats[2].get_direction());
+        assert_eq!(WorldState::Defeat, mouse_lost.get_state());
+        assert_eq!(WorldState::Defeat, rocket_lost.get_state());
     }
 
-    /// GIVEN an arrow against a wall
-    /// WHEN a walker is turned into the wall by the arrow
-    /// THEN the normal wall rule are applied, causing the walker to turn
+    /// GIVEN a running world with an arrow in stock
+    /// WHEN arrows are placed or removed
+    /// THEN the changes are refused
+    /// AND WHEN the world is stopped
+    /// THEN the walkers are reset and arrows can be placed
     #[test]
-    fn walker_turn_into_wall() {
+    fn arrows_only_editable_when_stopped() {
         let mut world = World::new();
-        world.set_arrow(4, 0, TileType::Up);
-        world.create_walker(5, 0, Direction::Left, WalkerType::Mouse);
+        world.arrow_stock[Direction::Up] = 1;
+        world.set_arrow(3, 3, TileType::Down);
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        world.start();
 
-        // Walk the walker into the arrow
         for _ in 0..60 {
             world.tick();
         }
 
-        // The walker is turned up by the arrow, then right by the wall
-        assert_eq!(Direction::Right, world.mice[0].get_direction());
+        assert_eq!(Err(PlacementError::NotStopped), world.place_arrow(2, 2, Direction::Up));
+        assert_eq!(Err(PlacementError::NotStopped), world.remove_arrow(3, 3));
+
+        world.stop();
+
+        assert_eq!(WorldState::Stopped, world.get_state());
+        assert_eq!(0, world.mice[0].get_x().integer_part());
+        assert_eq!(Ok(()), world.place_arrow(2, 2, Direction::Up));
+        assert_eq!(Ok(Direction::Down), world.remove_arrow(3, 3));
     }
 
-    /// GIVEN a newly created world
-    /// WHEN an arrow is set
-    /// THEN reading that arrow back returns the correct value
+    /// GIVEN a puzzle built by the puzzle! macro
+    /// WHEN it is loaded and serialised
+    /// THEN the output matches the input exactly
     #[test]
-    fn arrow_get_set() {
-        let mut world = World::new();
-
-        assert_eq!(TileType::Empty, world.get_arrow(0, 0));
-        world.set_arrow(0, 0, TileType::Right);
-        assert_eq!(TileType::Right, world.get_arrow(0, 0));
+    fn to_bytes_round_trips_puzzle() {
+        let data = world_macros::puzzle!("Round trip", "Test",
+        "┌───────────────────────────────────────────────────────────┐"
+        "│M>          Av C<                                          │"
+        "│                                                           │"
+        "│          R                                                │"
+        "│                                                           │"
+        "│     H           A<                                        │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "│                                                           │"
+        "└───────────────────────────────────────────────────────────┘");
+
+        assert_eq!(data, World::load(data).to_bytes());
+    }
 
-        assert_eq!(TileType::Empty, world.get_arrow(7, 3));
-        world.set_arrow(7, 3, TileType::Right);
-        assert_eq!(TileType::Right, world.get_arrow(7, 3));
+    /// GIVEN a world built with the editing methods
+    /// WHEN it is serialised and loaded
+    /// THEN the walls, walkers, rockets, holes and solution arrows are kept
+    #[test]
+    fn to_bytes_saves_edits() {
+        let mut world = World::new();
+        world.set_wall(5, 5, Direction::Right, true);
+        world.create_walker(1, 1, Direction::Down, WalkerType::Cat);
+        world.set_tile(2, 2, TileType::Rocket);
+        world.set_tile(3, 3, TileType::Hole);
+        world.set_arrow(4, 4, TileType::Left);
+        world.set_arrow(5, 4, TileType::Left);
+
+        // Replacing tiles leaves no trace of the old tile
+        world.set_tile(6, 6, TileType::Hole);
+        world.set_arrow(6, 6, TileType::Up);
+        world.set_arrow(7, 7, TileType::Up);
+        world.set_tile(7, 7, TileType::Empty);
+
+        let loaded = World::load(world.to_bytes());
+
+        assert_eq!(world.to_bytes(), loaded.to_bytes());
+        assert!(loaded.get_wall(5, 5, Direction::Right));
+        assert_eq!(1, loaded.cats.len());
+        assert_eq!(Direction::Down, loaded.cats[0].get_direction());
+        assert_eq!(TileType::Rocket, loaded.get_arrow(2, 2));
+        assert_eq!(TileType::Hole, loaded.get_arrow(3, 3));
+        assert_eq!(TileType::Empty, loaded.get_arrow(6, 6));
+        assert_eq!(2, loaded.get_arrow_stock()[Direction::Left]);
+        assert_eq!(1, loaded.get_arrow_stock()[Direction::Up]);
     }
 
-    /// GIVEN a world with a hole one unit to the right of a cat
-    /// WHEN the cat walks into the hole
-    /// THEN the cat is killed
-    /// AND the world state does not change
+    /// GIVEN a world with a walker and a spawner
+    /// WHEN a rocket or hole is set on their squares
+    /// THEN the tile is refused
+    /// AND the serialised and loaded world matches the live one
     #[test]
-    fn holes_kill_cats() {
+    fn to_bytes_keeps_entities_under_refused_tiles() {
         let mut world = World::new();
+        world.create_walker(1, 1, Direction::Down, WalkerType::Mouse);
+        world.create_spawner(2, 2, Direction::Up, WalkerType::Cat);
 
-        world.set_tile(1, 0, TileType::Hole);
-        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        assert_eq!(false, world.set_tile(1, 1, TileType::Rocket));
+        assert_eq!(false, world.set_tile(2, 2, TileType::Hole));
+        assert_eq!(TileType::Empty, world.get_arrow(1, 1));
+        assert_eq!(TileType::Empty, world.get_arrow(2, 2));
 
-        for _ in 0..89 {
-            world.tick();
-        }
+        let loaded = World::load(world.to_bytes());
 
-        // After 89 ticks the cat is still alive
-        assert_eq!(1, world.cats.len());
+        assert_eq!(world.to_bytes(), loaded.to_bytes());
+        assert_eq!(world.mice.len(), loaded.mice.len());
+        assert_eq!(world.get_spawners(), loaded.get_spawners());
+        assert_eq!(TileType::Empty, loaded.get_arrow(1, 1));
+        assert_eq!(TileType::Empty, loaded.get_arrow(2, 2));
+    }
 
-        // The 90th tick kills the cat as it falls into the hole
-        let world_state_change = world.tick();
+    /// GIVEN a loaded puzzle
+    /// WHEN the player places arrows and the simulation destroys a rocket
+    /// THEN the serialised form is unchanged
+    #[test]
+    fn to_bytes_ignores_play() {
+        let mut source = World::new();
+        source.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        source.set_tile(1, 0, TileType::Rocket);
+        source.set_arrow(5, 5, TileType::Up);
+        let data = source.to_bytes();
+        let mut world = World::load(data);
+
+        assert_eq!(Ok(()), world.place_arrow(4, 4, Direction::Up));
+        world.start();
+        for _ in 0..90 {
+            world.tick();
+        }
 
-        assert_eq!(WorldStateChange::NoChange, world_state_change);
-        assert_eq!(0, world.cats.len());
+        assert_eq!(TileType::RocketDestroyed, world.get_arrow(1, 0));
+        assert_eq!(data, world.to_bytes());
     }
 
-    /// GIVEN a world with a hole one unit to the right of a mouse
-    /// WHEN the mouse walks into the hole
-    /// THEN the mouse is killed
-    /// AND the world state changes to lose
+    /// GIVEN a world with custom rules part way through a simulation, with a diminished arrow and
+    /// a walker between squares
+    /// WHEN it is snapshotted and restored
+    /// THEN the restored world has the same rules and continues the simulation identically
     #[test]
-    fn holes_kill_mice_and_cause_loss() {
+    fn snapshot_restores_identical_simulation() {
         let mut world = World::new();
-
-        world.set_tile(1, 0, TileType::Hole);
+        world.set_ruleset(Ruleset {
+            turn_priority: [Turn::Left, Turn::Right, Turn::Back],
+            diminish_policy: DiminishPolicy::CatsTurned,
+            win_condition: WinCondition::MiceRescued(3),
+            loss_condition: LossCondition::MiceDie(2),
+            ..Ruleset::puzzle()
+        });
+        world.arrow_stock[Direction::Left] = 2;
         world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
-
-        for _ in 0..59 {
+        world.create_walker(0, 4, Direction::Right, WalkerType::Cat);
+        world.set_arrow(2, 4, TileType::Left);
+        world.set_tile(5, 0, TileType::Rocket);
+        world.start();
+        for _ in 0..200 {
             world.tick();
         }
+        assert_eq!(TileType::LeftHalf, world.get_arrow(2, 4));
+
+        let mut buffer = [0; SNAPSHOT_MAX_SIZE];
+        let length = world.snapshot(&mut buffer).unwrap();
+        let mut restored = <World>::restore(&buffer[..length]).unwrap();
+        assert_eq!(world.get_ruleset(), restored.get_ruleset());
+
+        let mut expected = [0; SNAPSHOT_MAX_SIZE];
+        let mut actual = [0; SNAPSHOT_MAX_SIZE];
+        for _ in 0..200 {
+            assert_eq!(world.tick(), restored.tick());
+            let expected_length = world.snapshot(&mut expected).unwrap();
+            let actual_length = restored.snapshot(&mut actual).unwrap();
+            assert_eq!(expected[..expected_length], actual[..actual_length]);
+        }
+        assert_eq!(world.get_state(), restored.get_state());
+        assert_eq!(world.get_arrow_stock(), restored.get_arrow_stock());
+    }
 
-        // After 59 ticks the mouse is still alive
-        assert_eq!(1, world.mice.len());
+    /// GIVEN a seeded world whose random number generator has been used
+    /// WHEN it is snapshotted and restored, then reset
+    /// THEN the restored generator continues the same sequence, and resetting restarts it from
+    /// the seed
+    #[test]
+    fn rng_is_snapshotted_and_reset() {
+        let mut world = World::new();
+        world.set_seed(42);
+        let first = world.get_rng_mut().next_u32();
+        world.get_rng_mut().next_u32();
+
+        let mut buffer = [0; SNAPSHOT_MAX_SIZE];
+        let length = world.snapshot(&mut buffer).unwrap();
+        let mut restored = <World>::restore(&buffer[..length]).unwrap();
+        assert_eq!(42, restored.get_seed());
+        assert_eq!(world.get_rng_mut().next_u32(), restored.get_rng_mut().next_u32());
+
+        restored.reset();
+        assert_eq!(first, restored.get_rng_mut().next_u32());
+    }
 
-        // The 90th tick kills the mouse as it falls into the hole
-        let world_state_change = world.tick();
+    /// GIVEN a buffer too small for a snapshot
+    /// WHEN a world is snapshotted into it
+    /// THEN the buffer is reported as full
+    #[test]
+    fn snapshot_buffer_full() {
+        let world = World::new();
+        let mut buffer = [0; 64];
 
-        assert_eq!(WorldStateChange::Lose, world_state_change);
-        assert_eq!(0, world.mice.len());
+        assert_eq!(Err(SnapshotError::BufferFull), world.snapshot(&mut buffer));
     }
 
-    /// GIVEN a world with a rocket one unit to the right of a cat
-    /// WHEN the cat walks into the rocket
-    /// THEN the cat is rescued
-    /// AND the world state changes to lose
+    /// GIVEN snapshots that are truncated, of another version or contain invalid values
+    /// WHEN they are restored
+    /// THEN the problem is reported
     #[test]
-    fn rockets_rescue_cats_and_cause_loss() {
+    fn restore_malformed_snapshots() {
         let mut world = World::new();
+        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+        let mut buffer = [0; SNAPSHOT_MAX_SIZE];
+        let length = world.snapshot(&mut buffer).unwrap();
+
+        assert_eq!(
+            Err(SnapshotError::Truncated),
+            <World>::restore(&buffer[..length - 1]).map(|_| ())
+        );
+        assert_eq!(
+            Err(SnapshotError::Corrupt),
+            <World>::restore(&buffer[..length + 1]).map(|_| ())
+        );
+
+        // The first tile follows the header, dimensions, data, state, ruleset and stock
+        let tiles_offset = SNAPSHOT_MAGIC.len() + 1 + 2 + 199 + 1 + SNAPSHOT_RULESET_SIZE + 4;
+        let mut corrupt = buffer;
+        corrupt[tiles_offset] = 0xFF;
+        assert_eq!(Err(SnapshotError::Corrupt), <World>::restore(&corrupt[..length]).map(|_| ()));
+
+        // The mouse follows both tile blocks and the count of mice. Its ID comes before its
+        // position, then its direction, type and state
+        let mouse_offset = tiles_offset + 2 * MAX_TILES + 2;
+        for (field, value) in [(4, 12), (4, 0xFF), (7, 9), (7, 0x80), (12, 1), (12, 2)] {
+            let mut corrupt = buffer;
+            corrupt[mouse_offset + field] = value;
+            assert_eq!(
+                Err(SnapshotError::Corrupt),
+                <World>::restore(&corrupt[..length]).map(|_| ())
+            );
+        }
 
-        world.set_tile(1, 0, TileType::Rocket);
-        world.create_walker(0, 0, Direction::Right, WalkerType::Cat);
+        buffer[SNAPSHOT_MAGIC.len()] += 1;
+        assert_eq!(
+            Err(SnapshotError::UnsupportedVersion),
+            <World>::restore(&buffer[..length]).map(|_| ())
+        );
+    }
 
-        for _ in 0..89 {
-            world.tick();
-        }
+    /// GIVEN a 5x3 puzzle, whose width does not pack evenly into the wall block
+    /// WHEN it is loaded and run
+    /// THEN the mouse follows the edge walls into the rocket in the far corner
+    #[test]
+    fn small_world_runs() {
+        let data = world_macros::puzzle!("Small", "Test",
+        "┌────────────────────────┐"
+        "│M>                      │"
+        "│                        │"
+        "│                        │"
+        "│                        │"
+        "│                    R   │"
+        "└────────────────────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        assert!(world.get_wall(4, 1, Direction::Right));
+        assert!(world.get_wall(2, 2, Direction::Down));
+        assert!(!world.get_wall(4, 1, Direction::Left));
+        assert_eq!(data, world.to_bytes());
+
+        assert_eq!(359, ticks_to_win(&mut world));
+    }
 
-        // After 89 ticks the cat is still alive
-        assert_eq!(1, world.cats.len());
+    /// Runs a world until it is won, returning the number of ticks that passed before the winning
+    /// tick
+    fn ticks_to_win<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const TILES: usize,
+        const DATA: usize,
+    >(
+        world: &mut World<WIDTH, HEIGHT, TILES, DATA>,
+    ) -> u32 {
+        world.start();
+        let mut ticks = 0;
+        while world.tick() == WorldStateChange::NoChange {
+            ticks += 1;
+            assert!(ticks < 1000);
+        }
 
-        // The 90th tick rescues the cat as it hits the rocket
-        let world_state_change = world.tick();
+        assert_eq!(WorldState::Success, world.get_state());
+        ticks
+    }
 
-        assert_eq!(0, world.cats.len());
-        assert_eq!(WorldStateChange::Lose, world_state_change);
+    /// GIVEN a mouse walking left through an open left edge
+    /// WHEN the world is run
+    /// THEN the mouse reappears on the right edge and reaches the rocket beside it
+    #[test]
+    fn walkers_wrap_off_left_edge() {
+        let data = world_macros::puzzle!("Wrap left", "Test",
+        "┌────────────────────────┐"
+        "│                        │"
+        "                          "
+        "      M<        R         "
+        "                          "
+        "│                        │"
+        "└────────────────────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        assert_eq!(179, ticks_to_win(&mut world));
     }
 
-    /// GIVEN a world with a rocket one unit to the right of a mouse
-    /// WHEN the mouse walks into the rocket
-    /// THEN the mouse is rescued
-    /// AND the world state changes to win
+    /// GIVEN a mouse walking right through an open right edge
+    /// WHEN the world is run
+    /// THEN the mouse reappears on the left edge and reaches the rocket beside it
     #[test]
-    fn rockets_rescue_mice_and_cause_win() {
-        let mut world = World::new();
+    fn walkers_wrap_off_right_edge() {
+        let data = world_macros::puzzle!("Wrap right", "Test",
+        "┌────────────────────────┐"
+        "│                        │"
+        "                          "
+        "      R         M>        "
+        "                          "
+        "│                        │"
+        "└────────────────────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        assert_eq!(179, ticks_to_win(&mut world));
+    }
 
-        world.set_tile(1, 0, TileType::Rocket);
-        world.create_walker(0, 0, Direction::Right, WalkerType::Mouse);
+    /// GIVEN a mouse walking up through an open top edge
+    /// WHEN the world is run
+    /// THEN the mouse reappears on the bottom edge and reaches the rocket above it
+    #[test]
+    fn walkers_wrap_off_top_edge() {
+        let data = world_macros::puzzle!("Wrap top", "Test",
+        "┌──────────    ──────────┐"
+        "│          M^            │"
+        "                          "
+        "│          R             │"
+        "                          "
+        "│                        │"
+        "└──────────    ──────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        assert_eq!(119, ticks_to_win(&mut world));
+    }
 
-        for _ in 0..59 {
-            world.tick();
+    /// GIVEN a mouse and a cat walking towards each other through an open edge
+    /// WHEN the world is run
+    /// THEN they collide across the edge and the mouse is eaten
+    #[test]
+    fn walkers_collide_across_open_edge() {
+        let data = world_macros::puzzle!("Wrap collide", "Test",
+        "┌────────────────────────┐"
+        "│                        │"
+        "                          "
+        " M<                  C>   "
+        "                          "
+        "│                        │"
+        "└────────────────────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+        world.start();
+
+        // The walkers start a square apart around the edge, and close a tenth of a square each
+        // tick until they are within half a square
+        for _ in 0..17 {
+            assert_eq!(WorldStateChange::NoChange, world.tick());
         }
+        assert_eq!(WorldStateChange::Lose, world.tick());
+        assert_eq!(1, world.get_walker_counts(WalkerType::Mouse).dead);
+    }
 
-        // After 59 ticks the mouse is still alive
-        assert_eq!(1, world.mice.len());
-
-        // The 90th tick rescues the mouse as it falls into the hole
-        let world_state_change = world.tick();
-
-        assert_eq!(WorldStateChange::Win, world_state_change);
-        assert_eq!(0, world.mice.len());
+    /// GIVEN a mouse walking down through an open bottom edge
+    /// WHEN the world is run
+    /// THEN the mouse reappears on the top edge and reaches the rocket below it
+    #[test]
+    fn walkers_wrap_off_bottom_edge() {
+        let data = world_macros::puzzle!("Wrap bottom", "Test",
+        "┌──────────    ──────────┐"
+        "│                        │"
+        "                          "
+        "│          R             │"
+        "                          "
+        "│          Mv            │"
+        "└──────────    ──────────┘");
+        let mut world = World::<5, 3, 15, { world_data_size(5, 3) }>::load_sized(data);
+
+        assert_eq!(119, ticks_to_win(&mut world));
     }
 }
@@ -0,0 +1,57 @@
+use crate::{Direction, RocketEvent, WalkerId, WalkerType};
+
+/// The ways a walker can die
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DeathCause {
+    /// The walker fell into a hole
+    Hole,
+    /// The walker was a mouse, and was eaten by a cat
+    Eaten,
+}
+
+/// Something that happened during a simulation step, so that it can be animated or played as a
+/// sound. Coordinates are those of the square the walker or arrow was on. Walkers are identified
+/// by ID, so that death and rescue animations can carry on after the walker has been removed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WorldEvent {
+    /// A walker entered a rocket
+    Rocket { id: WalkerId, event: RocketEvent },
+    /// A walker died
+    Died {
+        id: WalkerId,
+        x: usize,
+        y: usize,
+        walker_type: WalkerType,
+        cause: DeathCause,
+    },
+    /// A cat wore down an arrow it walked onto, shrinking it to a half arrow. Which cats wear
+    /// down arrows depends on the diminish policy: puzzles only count cats turned around, while
+    /// other rulesets may count every cat turned
+    ArrowDiminished { x: usize, y: usize },
+    /// An arrow was removed, either worn away by cats or expired at the end of its lifetime
+    ArrowRemoved { x: usize, y: usize },
+    /// A walker was turned by an arrow
+    Turned {
+        id: WalkerId,
+        x: usize,
+        y: usize,
+        walker_type: WalkerType,
+        direction: Direction,
+    },
+    /// A walker was turned by a wall
+    WallBounce {
+        id: WalkerId,
+        x: usize,
+        y: usize,
+        walker_type: WalkerType,
+        direction: Direction,
+    },
+    /// A spawner created a walker
+    Spawned {
+        id: WalkerId,
+        x: usize,
+        y: usize,
+        walker_type: WalkerType,
+        direction: Direction,
+    },
+}
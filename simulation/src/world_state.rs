@@ -13,5 +13,11 @@ pub enum WorldState {
 pub enum WorldStateChange {
     Win,
     Lose,
+    /// A cat destroyed a rocket. This is also a loss
+    RocketDestroyed,
+    /// The walkers, tiles, spawners and random number generator returned to a configuration seen
+    /// before, judged by a hash of the configuration, so the world is looping and will never be
+    /// won or lost. This is also a loss
+    Stalemate,
     NoChange,
 }
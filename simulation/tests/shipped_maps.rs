@@ -0,0 +1,25 @@
+// The maps shipped in the binary. Included directly so they can be checked on the host
+#[path = "../../bin/src/maps.rs"]
+mod maps;
+
+use simulation::{verify_solution, VerificationOutcome, World};
+
+/// The tick budget for a shipped map. Five minutes at 60fps
+const MAX_TICKS: u32 = 60 * 60 * 5;
+
+/// The ticks the authored solution of each shipped map takes to win, in the same order as the
+/// maps. A map added without its count here will not compile
+const SOLUTION_TICKS: [u32; maps::MAPS.len()] = [6360];
+
+/// GIVEN every map shipped in the binary
+/// WHEN the authored solution of each is run
+/// THEN all mice are rescued, in the expected number of ticks
+#[test]
+fn shipped_maps_are_solvable() {
+    for (index, (map, ticks)) in maps::MAPS.iter().zip(SOLUTION_TICKS).enumerate() {
+        let verification = verify_solution(&World::load(**map), MAX_TICKS);
+
+        assert_eq!(VerificationOutcome::Win, verification.outcome, "map {}", index);
+        assert_eq!(ticks, verification.ticks, "map {}", index);
+    }
+}
@@ -1,5 +1,4 @@
 extern crate proc_macro;
-use debugless_unwrap::*;
 use itertools::Itertools;
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
@@ -9,21 +8,15 @@ use syn::{
     parse_macro_input, Error, LitStr, Token,
 };
 
-/// The width of the world
-const WORLD_WIDTH: usize = 12;
-/// The height of the world
-const WORLD_HEIGHT: usize = 9;
 const MAP_NAME_SIZE: usize = 32;
 const MAP_NAME_OFFSET: usize = 0;
 /// The map name field
 const MAP_AUTHOR_SIZE: usize = 32;
 const MAP_AUTHOR_OFFSET: usize = MAP_NAME_OFFSET + MAP_NAME_SIZE;
-/// The size of the wall block
-const WALL_BLOCK_SIZE: usize = 27;
+/// The offset of the wall block. Its size depends on the world dimensions
 const WALL_BLOCK_OFFSET: usize = MAP_AUTHOR_OFFSET + MAP_AUTHOR_SIZE;
-/// The size and offset of the tile block
-const TILE_BLOCK_SIZE: usize = 108;
-const TILE_BLOCK_OFFSET: usize = WALL_BLOCK_OFFSET + WALL_BLOCK_SIZE;
+/// The largest supported width or height
+const MAX_DIMENSION: usize = i8::MAX as usize;
 /// The masks used to pack the left walls. There are four walls packed into each byte
 const LEFT_WALL_MASK: [u8; 4] = [0b00000010, 0b00001000, 0b00100000, 0b10000000];
 /// The masks uses to pack the top walls.
@@ -34,6 +27,8 @@ const ENTITY_TYPE_MOUSE: u8 = 0b00100000;
 const ENTITY_TYPE_CAT: u8 = 0b01000000;
 const ENTITY_TYPE_ROCKET: u8 = 0b01100000;
 const ENTITY_TYPE_HOLE: u8 = 0b10000000;
+const ENTITY_TYPE_MOUSE_SPAWNER: u8 = 0b10100000;
+const ENTITY_TYPE_CAT_SPAWNER: u8 = 0b11000000;
 
 // The directions of entities in the tile block
 const ENTITY_DIRECTION_UP: u8 = 0b00000000;
@@ -41,46 +36,82 @@ const ENTITY_DIRECTION_DOWN: u8 = 0b00001000;
 const ENTITY_DIRECTION_LEFT: u8 = 0b00010000;
 const ENTITY_DIRECTION_RIGHT: u8 = 0b00011000;
 
+/// The flag indicating an arrow is present
+const ARROW_PRESENT: u8 = 0b00000100;
+
 /// The values to use for packed arrow directions
 const ARROW_DIRECTION_UP: u8 = 0b00000000;
 const ARROW_DIRECTION_DOWN: u8 = 0b00000001;
 const ARROW_DIRECTION_LEFT: u8 = 0b00000010;
 const ARROW_DIRECTION_RIGHT: u8 = 0b00000011;
 
+/// The size of the world, as inferred from the ASCII art
+#[derive(Copy, Clone)]
+struct Dimensions {
+    width: usize,
+    height: usize,
+}
+
+impl Dimensions {
+    /// Gets the size of the wall block, with four squares packed into each byte
+    const fn wall_block_size(self) -> usize {
+        (self.width * self.height).div_ceil(4)
+    }
+
+    /// Gets the offset of the tile block
+    const fn tile_block_offset(self) -> usize {
+        WALL_BLOCK_OFFSET + self.wall_block_size()
+    }
+
+    /// Gets the size of the tile block, with one byte per square
+    const fn tile_block_size(self) -> usize {
+        self.width * self.height
+    }
+
+    /// Gets the length of each line of ASCII art
+    const fn line_length(self) -> usize {
+        self.width * 5 + 1
+    }
+}
+
 /// Gets the index into the wall data of a particular wall, and the mask required to
 /// get/set it.
 ///
 /// Arguments:
-/// * `x`: The x coordinate to set. Must be in range 0-11
-/// * `y`: The y coordinate to set. Must be in range 0-8
+/// * `dimensions`: The size of the world
+/// * `x`: The x coordinate to set. Must be in range 0..width
+/// * `y`: The y coordinate to set. Must be in range 0..height
 ///
 /// Return value:
 /// A tuple containing the wall index and the mask required for left walls
-const fn get_l_wall_index_and_mask(x: usize, y: usize) -> (usize, u8) {
-    assert!(x < WORLD_WIDTH);
-    assert!(y < WORLD_HEIGHT);
-    ((y * WORLD_WIDTH + x) / 4, LEFT_WALL_MASK[x & 0x03])
+const fn get_l_wall_index_and_mask(dimensions: Dimensions, x: usize, y: usize) -> (usize, u8) {
+    assert!(x < dimensions.width);
+    assert!(y < dimensions.height);
+    let index = y * dimensions.width + x;
+    (index / 4, LEFT_WALL_MASK[index & 0x03])
 }
 
 /// Gets the index into the wall data of a particular wall, and the mask required to
 /// get/set it.
 ///
 /// Arguments:
-/// * `x`: The x coordinate to set. Must be in range 0-11
-/// * `y`: The y coordinate to set. Must be in range 0-8
+/// * `dimensions`: The size of the world
+/// * `x`: The x coordinate to set. Must be in range 0..width
+/// * `y`: The y coordinate to set. Must be in range 0..height
 ///
 /// Return value:
 /// A tuple containing the wall index and the mask required for top walls
-const fn get_t_wall_index_and_mask(x: usize, y: usize) -> (usize, u8) {
-    assert!(x < WORLD_WIDTH);
-    assert!(y < WORLD_HEIGHT);
-    ((y * WORLD_WIDTH + x) / 4, TOP_WALL_MASK[x & 0x03])
+const fn get_t_wall_index_and_mask(dimensions: Dimensions, x: usize, y: usize) -> (usize, u8) {
+    assert!(x < dimensions.width);
+    assert!(y < dimensions.height);
+    let index = y * dimensions.width + x;
+    (index / 4, TOP_WALL_MASK[index & 0x03])
 }
 
 struct PuzzleMacroInput {
     pub name: LitStr,
     pub author: LitStr,
-    pub body: [LitStr; 19],
+    pub body: Vec<LitStr>,
 }
 
 impl Parse for PuzzleMacroInput {
@@ -92,18 +123,14 @@ impl Parse for PuzzleMacroInput {
         let author: LitStr = input.parse()?;
         input.parse::<Token![,]>()?;
 
-        // TODO: Improve error reporting here if there are an incorrect number of lines
+        // The body is every remaining line. Its size is checked by get_dimensions
         let mut body: Vec<LitStr> = Vec::new();
-        for _row_index in 0..19 {
+        while !input.is_empty() {
             let row: LitStr = input.parse()?;
             body.push(row);
         }
 
-        Ok(PuzzleMacroInput {
-            name,
-            author,
-            body: body.try_into().debugless_unwrap(),
-        })
+        Ok(PuzzleMacroInput { name, author, body })
     }
 }
 
@@ -143,6 +170,43 @@ fn check_and_add_string(
     None
 }
 
+/// Infers the size of the world from the ASCII art. Each row of squares is two lines, with a
+/// final line for the bottom walls, and each square is five characters wide with a final
+/// character for the right walls
+/// Arguments:
+/// * `input`: The parsed macro input
+fn get_dimensions(input: &PuzzleMacroInput) -> Result<Dimensions, TokenStream> {
+    let line_count = input.body.len();
+    if line_count < 3 || line_count % 2 != 1 {
+        return Err(quote_spanned! {
+            input.author.span() => compile_error!("Puzzle must have an odd number of lines, with at least one row of squares")
+        }
+        .into());
+    }
+
+    let first_line = &input.body[0];
+    let line_length = first_line.value().chars().count();
+    if line_length < 6 || line_length % 5 != 1 {
+        return Err(quote_spanned! {
+            first_line.span() => compile_error!("Line length must be five characters per square, plus one")
+        }
+        .into());
+    }
+
+    let dimensions = Dimensions {
+        width: (line_length - 1) / 5,
+        height: (line_count - 1) / 2,
+    };
+    if dimensions.width > MAX_DIMENSION || dimensions.height > MAX_DIMENSION {
+        return Err(quote_spanned! {
+            first_line.span() => compile_error!("Puzzle is too large")
+        }
+        .into());
+    }
+
+    Ok(dimensions)
+}
+
 /// Checks that the top/bottom lines are consistent
 /// OK:
 /// ┌────┬────┬────┬────┬────┬────┬────┬────┬────┬────┬────┬────┐
@@ -154,12 +218,16 @@ fn check_and_add_string(
 /// * `top`: The top line
 /// * `max_size`: The maximum length in bytes of the string
 /// * `output`: The slice to write to. Should be same size as max_size
-fn check_top_bottom_consistency(top: &LitStr, bottom: &LitStr) -> Option<TokenStream> {
+fn check_top_bottom_consistency(
+    dimensions: Dimensions,
+    top: &LitStr,
+    bottom: &LitStr,
+) -> Option<TokenStream> {
     // Check the top-bottom markers are consistent
     let top_value = top.value();
     let bottom_value = bottom.value();
 
-    for col_index in 0..WORLD_WIDTH {
+    for col_index in 0..dimensions.width {
         // Check that the top and bottom are the same
         if top_value.chars().nth(col_index * 5 + 1) != bottom_value.chars().nth(col_index * 5 + 1) {
             return Some(
@@ -174,15 +242,16 @@ fn check_top_bottom_consistency(top: &LitStr, bottom: &LitStr) -> Option<TokenSt
     None
 }
 
-/// Checks that all rows are 61 characters long
+/// Checks that all rows are the same length as the first
 /// Arguments:
+/// * `dimensions`: The size of the world
 /// * `rows`: All rows in the input
-fn check_line_lengths(rows: &[LitStr]) -> Option<TokenStream> {
+fn check_line_lengths(dimensions: Dimensions, rows: &[LitStr]) -> Option<TokenStream> {
     for row in rows {
-        if row.value().chars().count() != 61 {
+        if row.value().chars().count() != dimensions.line_length() {
             return Some(
                 quote_spanned! {
-                    row.span() => compile_error!("Line must be 61 characters long")
+                    row.span() => compile_error!("All lines must be the same length")
                 }
                 .into(),
             );
@@ -194,10 +263,10 @@ fn check_line_lengths(rows: &[LitStr]) -> Option<TokenStream> {
 /// Checks that within a row, the four top characters in a cell are consistent
 /// Arguments:
 /// * `rows`: The even rows
-fn check_cell_consistency(rows: &Vec<&LitStr>) -> Option<TokenStream> {
+fn check_cell_consistency(dimensions: Dimensions, rows: &Vec<&LitStr>) -> Option<TokenStream> {
     for row in rows {
         let row_value = row.value();
-        for col_index in 0..WORLD_WIDTH {
+        for col_index in 0..dimensions.width {
             for intracell_index in 1..5 {
                 if row_value.chars().nth(col_index * 5 + 1)
                     != row_value.chars().nth(col_index * 5 + intracell_index)
@@ -240,15 +309,20 @@ fn check_left_right_consistency(rows: &Vec<&LitStr>) -> Option<TokenStream> {
 /// Arguments:
 /// * `rows`: The even rows, excluding the last one
 /// * `output`: The wall block in the output
-fn extract_top_walls(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream> {
+fn extract_top_walls(
+    dimensions: Dimensions,
+    rows: &Vec<&LitStr>,
+    output: &mut [u8],
+) -> Option<TokenStream> {
     for (row_index, line_literal) in rows.iter().enumerate() {
         let line = line_literal.value();
 
-        for col_index in 0..WORLD_WIDTH {
+        for col_index in 0..dimensions.width {
             match line.chars().nth(col_index * 5 + 1).unwrap() {
                 '─' => {
                     // Set the appropriate bit in the output
-                    let (wall_index, mask) = get_t_wall_index_and_mask(col_index, row_index);
+                    let (wall_index, mask) =
+                        get_t_wall_index_and_mask(dimensions, col_index, row_index);
                     let byte = &mut output[wall_index];
                     *byte = *byte | mask;
                 }
@@ -274,15 +348,20 @@ fn extract_top_walls(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStre
 /// Arguments:
 /// * `rows`: The odd rows
 /// * `output`: The wall block in the output
-fn extract_left_walls(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream> {
+fn extract_left_walls(
+    dimensions: Dimensions,
+    rows: &Vec<&LitStr>,
+    output: &mut [u8],
+) -> Option<TokenStream> {
     for (row_index, line_literal) in rows.iter().enumerate() {
         let line = line_literal.value();
 
         // Set appropriate bits for left walls
-        for col_index in 0..WORLD_WIDTH {
+        for col_index in 0..dimensions.width {
             match line.chars().nth(col_index * 5).unwrap() {
                 '│' => {
-                    let (wall_index, mask) = get_l_wall_index_and_mask(col_index, row_index);
+                    let (wall_index, mask) =
+                        get_l_wall_index_and_mask(dimensions, col_index, row_index);
                     let byte = &mut output[wall_index];
                     *byte = *byte | mask;
                 }
@@ -308,13 +387,17 @@ fn extract_left_walls(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStr
 /// Arguments:
 /// * `rows`: The odd rows
 /// * `output`: The tile block in the output
-fn extract_arrows(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream> {
+fn extract_arrows(
+    dimensions: Dimensions,
+    rows: &Vec<&LitStr>,
+    output: &mut [u8],
+) -> Option<TokenStream> {
     for (row_index, line_literal) in rows.iter().enumerate() {
         let line = line_literal.value();
 
         // Set appropriate bits for left walls
-        for col_index in 0..WORLD_WIDTH {
-            let arrow_index = col_index + row_index * WORLD_WIDTH;
+        for col_index in 0..dimensions.width {
+            let arrow_index = col_index + row_index * dimensions.width;
             let byte = &mut output[arrow_index];
 
             match (
@@ -322,16 +405,16 @@ fn extract_arrows(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
                 line.chars().nth(col_index * 5 + 4).unwrap(),
             ) {
                 ('A', '<') => {
-                    *byte = *byte | ARROW_DIRECTION_LEFT;
+                    *byte = *byte | ARROW_PRESENT | ARROW_DIRECTION_LEFT;
                 }
                 ('A', '>') => {
-                    *byte = *byte | ARROW_DIRECTION_RIGHT;
+                    *byte = *byte | ARROW_PRESENT | ARROW_DIRECTION_RIGHT;
                 }
                 ('A', '^') => {
-                    *byte = *byte | ARROW_DIRECTION_UP;
+                    *byte = *byte | ARROW_PRESENT | ARROW_DIRECTION_UP;
                 }
                 ('A', 'v') => {
-                    *byte = *byte | ARROW_DIRECTION_DOWN;
+                    *byte = *byte | ARROW_PRESENT | ARROW_DIRECTION_DOWN;
                 }
                 (' ', ' ') => { /* No arrows and no direction, do nothing */ }
                 ('A', _) => {
@@ -355,13 +438,17 @@ fn extract_arrows(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
 /// Arguments:
 /// * `rows`: The odd rows
 /// * `output`: The tile block in the output
-fn extract_tiles(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream> {
+fn extract_tiles(
+    dimensions: Dimensions,
+    rows: &Vec<&LitStr>,
+    output: &mut [u8],
+) -> Option<TokenStream> {
     for (row_index, line_literal) in rows.iter().enumerate() {
         let line = line_literal.value();
 
         // Set appropriate bits for left walls
-        for col_index in 0..WORLD_WIDTH {
-            let tile_index = col_index + row_index * WORLD_WIDTH;
+        for col_index in 0..dimensions.width {
+            let tile_index = col_index + row_index * dimensions.width;
             let byte = &mut output[tile_index];
 
             match (
@@ -393,6 +480,30 @@ fn extract_tiles(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
                 ('C', 'v') => {
                     *byte = *byte | ENTITY_TYPE_CAT | ENTITY_DIRECTION_DOWN;
                 }
+                ('S', '<') => {
+                    *byte = *byte | ENTITY_TYPE_MOUSE_SPAWNER | ENTITY_DIRECTION_LEFT;
+                }
+                ('S', '>') => {
+                    *byte = *byte | ENTITY_TYPE_MOUSE_SPAWNER | ENTITY_DIRECTION_RIGHT;
+                }
+                ('S', '^') => {
+                    *byte = *byte | ENTITY_TYPE_MOUSE_SPAWNER | ENTITY_DIRECTION_UP;
+                }
+                ('S', 'v') => {
+                    *byte = *byte | ENTITY_TYPE_MOUSE_SPAWNER | ENTITY_DIRECTION_DOWN;
+                }
+                ('K', '<') => {
+                    *byte = *byte | ENTITY_TYPE_CAT_SPAWNER | ENTITY_DIRECTION_LEFT;
+                }
+                ('K', '>') => {
+                    *byte = *byte | ENTITY_TYPE_CAT_SPAWNER | ENTITY_DIRECTION_RIGHT;
+                }
+                ('K', '^') => {
+                    *byte = *byte | ENTITY_TYPE_CAT_SPAWNER | ENTITY_DIRECTION_UP;
+                }
+                ('K', 'v') => {
+                    *byte = *byte | ENTITY_TYPE_CAT_SPAWNER | ENTITY_DIRECTION_DOWN;
+                }
                 ('R', ' ') => {
                     *byte = *byte | ENTITY_TYPE_ROCKET;
                 }
@@ -404,6 +515,11 @@ fn extract_tiles(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
                         line_literal.span() => compile_error!("If a mouse or cat is specified then it must be followed by one of <>^v")
                     }.into());
                 }
+                ('S' | 'K', _) => {
+                    return Some(quote_spanned! {
+                        line_literal.span() => compile_error!("If a spawner is specified then it must be followed by one of <>^v")
+                    }.into());
+                }
                 ('R' | 'H', _) => {
                     return Some(quote_spanned! {
                         line_literal.span() => compile_error!("If a rocket or hole is specified then it must be followed by a blank space")
@@ -438,6 +554,8 @@ fn extract_tiles(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
 /// < > ^ v
 /// Walker/rocket/hole symbols:
 /// M C R H
+/// Mouse/cat spawner symbols, followed by the direction walkers are spawned in:
+/// S K
 /// ┌────┬────┬────┬────┬────┬────┬────┬────┬────┬────┬────┬────┐
 /// │<M< │    │    │    │    │    │    │    │    │    │    │    │
 /// ├────┴────┼────┴────┴────┼────┼────┼────┼────┼────┼────┼────┤
@@ -447,6 +565,10 @@ fn extract_tiles(rows: &Vec<&LitStr>, output: &mut [u8]) -> Option<TokenStream>
 /// └────┬────┼──── ──── ──── ──── ──── ──── ──── ──── ──── ────
 ///
 ///
+/// The size of the world is inferred from the drawing, so the output is an array of
+/// world_data_size(width, height) bytes. The usual 12x9 world is 61 characters wide and 19
+/// lines tall
+///
 /// Usage:
 /// let map = puzzle!("Name", "Author", "....")
 #[proc_macro]
@@ -455,7 +577,14 @@ pub fn puzzle(tokens: TokenStream) -> TokenStream {
     // dbg!(&tokens);
 
     let input = parse_macro_input!(tokens as PuzzleMacroInput);
-    let mut output = [0u8; 199];
+    let dimensions = match get_dimensions(&input) {
+        Ok(dimensions) => dimensions,
+        Err(x) => return x,
+    };
+    let wall_block = WALL_BLOCK_OFFSET..WALL_BLOCK_OFFSET + dimensions.wall_block_size();
+    let tile_block = dimensions.tile_block_offset()
+        ..dimensions.tile_block_offset() + dimensions.tile_block_size();
+    let mut output = vec![0u8; tile_block.end];
 
     // Omit the last row from even_rows, as this is purely for looks
     let even_rows: Vec<&LitStr> = input.body.iter().step_by(2).dropping_back(1).collect();
@@ -478,17 +607,19 @@ pub fn puzzle(tokens: TokenStream) -> TokenStream {
     }
 
     // Check top-bottom wraparound edges for consistency
-    if let Some(x) = check_top_bottom_consistency(&input.body[0], &input.body[18]) {
+    if let Some(x) =
+        check_top_bottom_consistency(dimensions, &input.body[0], &input.body[input.body.len() - 1])
+    {
         return x;
     }
 
     // Check that within each cell, all top walls are the same
-    if let Some(x) = check_cell_consistency(&even_rows) {
+    if let Some(x) = check_cell_consistency(dimensions, &even_rows) {
         return x;
     }
 
     // Check line lengths
-    if let Some(x) = check_line_lengths(&input.body) {
+    if let Some(x) = check_line_lengths(dimensions, &input.body) {
         return x;
     }
 
@@ -498,34 +629,22 @@ pub fn puzzle(tokens: TokenStream) -> TokenStream {
     }
 
     // Read the top walls
-    if let Some(x) = extract_top_walls(
-        &even_rows,
-        &mut output[WALL_BLOCK_OFFSET..WALL_BLOCK_OFFSET + WALL_BLOCK_SIZE],
-    ) {
+    if let Some(x) = extract_top_walls(dimensions, &even_rows, &mut output[wall_block.clone()]) {
         return x;
     }
 
     // Read the left walls
-    if let Some(x) = extract_left_walls(
-        &odd_rows,
-        &mut output[WALL_BLOCK_OFFSET..WALL_BLOCK_OFFSET + WALL_BLOCK_SIZE],
-    ) {
+    if let Some(x) = extract_left_walls(dimensions, &odd_rows, &mut output[wall_block]) {
         return x;
     }
 
     // Read the arrows
-    if let Some(x) = extract_arrows(
-        &odd_rows,
-        &mut output[TILE_BLOCK_OFFSET..TILE_BLOCK_OFFSET + TILE_BLOCK_SIZE],
-    ) {
+    if let Some(x) = extract_arrows(dimensions, &odd_rows, &mut output[tile_block.clone()]) {
         return x;
     }
 
     // Read the tiles
-    if let Some(x) = extract_tiles(
-        &odd_rows,
-        &mut output[TILE_BLOCK_OFFSET..TILE_BLOCK_OFFSET + TILE_BLOCK_SIZE],
-    ) {
+    if let Some(x) = extract_tiles(dimensions, &odd_rows, &mut output[tile_block]) {
         return x;
     }
 
@@ -0,0 +1,13 @@
+use world_macros::puzzle;
+
+// Given a line shorter than the first line the test should fail to compile
+fn main() {
+    let _map = puzzle!("Name", "Author",
+    "┌───────────────────┐"
+    "│                   │"
+    "│                   │"
+    "│              │"
+    "│                   │"
+    "│                   │"
+    "└───────────────────┘");
+}
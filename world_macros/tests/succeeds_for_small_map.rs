@@ -0,0 +1,16 @@
+use world_macros::puzzle;
+
+// Given a 4x3 map the compile passes, and the output is sized for 4x3
+fn main() {
+    let map: [u8; 64 + 3 + 12] = puzzle!("Name", "Author",
+    "┌───────────────────┐"
+    "│M>                 │"
+    "│                   │"
+    "│          R        │"
+    "│                   │"
+    "│                   │"
+    "└───────────────────┘");
+
+    assert_eq!(0b00111000, map[64 + 3]);
+    assert_eq!(0b01100000, map[64 + 3 + 6]);
+}
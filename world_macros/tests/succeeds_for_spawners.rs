@@ -0,0 +1,17 @@
+use world_macros::puzzle;
+
+// Given a map with a mouse spawner and a cat spawner the compile passes, and the spawners are
+// packed with their directions
+fn main() {
+    let map: [u8; 64 + 3 + 12] = puzzle!("Name", "Author",
+    "┌───────────────────┐"
+    "│S>                 │"
+    "│                   │"
+    "│          Kv       │"
+    "│                   │"
+    "│                   │"
+    "└───────────────────┘");
+
+    assert_eq!(0b10111000, map[64 + 3]);
+    assert_eq!(0b11001000, map[64 + 3 + 6]);
+}